@@ -8,7 +8,7 @@
 use anyhow::Result;
 use dx_font::download::FontDownloader;
 use dx_font::search::FontSearch;
-use dx_font::cdn::{CdnUrlGenerator, FontCdnUrls};
+use dx_font::cdn::CdnUrlGenerator;
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -40,11 +40,13 @@ async fn main() -> Result<()> {
         &["woff2"],
         &["latin"],
     ).await {
-        Ok(path) => {
+        Ok(paths) => {
             let elapsed = start.elapsed();
-            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let size: u64 = paths.iter()
+                .map(|p| std::fs::metadata(p).map(|m| m.len()).unwrap_or(0))
+                .sum();
             println!("✅ Downloaded 'Roboto' in {:?}", elapsed);
-            println!("   File: {}", path.display());
+            println!("   Files: {}", paths.len());
             println!("   Size: {} bytes", size);
             println!("   Speed: {:.2} KB/s\n", (size as f64 / 1024.0) / elapsed.as_secs_f64());
         }
@@ -117,9 +119,9 @@ async fn main() -> Result<()> {
             &["woff2"],
             &["latin"],
         ).await {
-            Ok(path) => {
+            Ok(paths) => {
                 let download_time = download_start.elapsed();
-                println!("  ✅ Downloaded in {:?}: {}", download_time, path.display());
+                println!("  ✅ Downloaded {} files in {:?}", paths.len(), download_time);
             }
             Err(e) => {
                 println!("  ⚠️  Download failed: {}", e);
@@ -222,7 +224,7 @@ fn generate_multi_font_preview(fonts: &[(&str, &str)]) -> String {
     let mut font_links = String::new();
     let mut font_samples = String::new();
     
-    for (name, id) in fonts {
+    for (name, _id) in fonts {
         font_links.push_str(&format!(
             r#"    <link href="https://fonts.googleapis.com/css2?family={}&display=swap" rel="stylesheet">
 "#,
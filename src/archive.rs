@@ -0,0 +1,305 @@
+//! Archive extraction
+//!
+//! Several providers ship a family as a ZIP package (Google Webfonts Helper,
+//! DaFont, FontSpace, Font Squirrel, GitHub releases). This module unpacks
+//! those packages, keeps only font and license files, and lays the fonts out as
+//! `<output>/<Family>/<Family>-<Weight><Style>.<ext>`.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::models::{DownloadOptions, FontStyle, FontWeight};
+
+/// File extensions treated as fonts
+pub const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "woff", "woff2"];
+
+/// Weight and style read from a font file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontFileInfo {
+    /// `None` for variable fonts, which cover a whole weight range
+    pub weight: Option<FontWeight>,
+    pub style: FontStyle,
+}
+
+impl FontFileInfo {
+    pub fn is_variable(&self) -> bool {
+        self.weight.is_none()
+    }
+
+    /// `Bold`, `BoldItalic`, `Italic` (regular italic), `Variable`, ...
+    pub fn suffix(&self) -> String {
+        let weight = match self.weight {
+            Some(w) => weight_name(w),
+            None => "Variable",
+        };
+        match (self.weight, self.style) {
+            (Some(FontWeight::Regular), FontStyle::Italic) => "Italic".to_string(),
+            (_, FontStyle::Italic) => format!("{}Italic", weight),
+            (_, FontStyle::Normal) => weight.to_string(),
+        }
+    }
+}
+
+/// Whether `path` looks like a ZIP archive
+pub fn is_archive(path: &Path) -> bool {
+    extension(path).as_deref() == Some("zip")
+}
+
+/// Font format of `path` (`ttf`, `woff2`, ...), if it is a font file
+pub fn font_format(path: &Path) -> Option<String> {
+    extension(path).filter(|ext| FONT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Whether `path` is a license or copyright notice worth keeping
+pub fn is_license_file(path: &Path) -> bool {
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    let stem = stem.to_lowercase();
+    let plain_text = matches!(extension(path).as_deref(), None | Some("txt" | "md" | "html"));
+    plain_text && ["license", "licence", "ofl", "copying", "copyright"].iter().any(|k| stem.starts_with(k))
+}
+
+/// Directory and file name prefix for a family: `open-sans` becomes `OpenSans`
+pub fn family_dir_name(family: &str) -> String {
+    family.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Read weight and style from a file name such as `Roboto-BoldItalic.ttf`,
+/// `roboto-v30-latin-700italic.woff2` or `Inter[opsz,wght].ttf`
+pub fn parse_font_file(path: &Path) -> FontFileInfo {
+    let stem = path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let style = if stem.contains("italic") || stem.contains("oblique") {
+        FontStyle::Italic
+    } else {
+        FontStyle::Normal
+    };
+
+    let variable = stem.contains('[') || stem.contains("variable") || stem.ends_with("-vf") || stem.ends_with("_vf");
+    if variable {
+        return FontFileInfo { weight: None, style };
+    }
+
+    FontFileInfo { weight: Some(parse_weight(&stem)), style }
+}
+
+/// Whether a font file passes the format, weight and style filters of `options`
+///
+/// Variable fonts pass any weight filter since they contain every weight.
+pub fn wanted(format: &str, info: &FontFileInfo, options: &DownloadOptions) -> bool {
+    let format_ok = options.formats.is_empty()
+        || options.formats.iter().any(|f| f.eq_ignore_ascii_case(format));
+    let weight_ok = match (&options.weights, info.weight) {
+        (Some(weights), Some(weight)) => weights.contains(&weight),
+        _ => true,
+    };
+    let style_ok = options.styles.as_ref().is_none_or(|styles| styles.contains(&info.style));
+    format_ok && weight_ok && style_ok
+}
+
+/// Extract the fonts and license files of `archive` for `family`
+///
+/// Returns every file written, fonts first, in archive order. Nested archives
+/// and other files are skipped. With `options.prefer_variable`, static fonts
+/// are dropped for styles the archive also has a variable font for.
+pub fn extract(archive: &Path, family: &str, options: &DownloadOptions) -> Result<Vec<PathBuf>> {
+    let archive_error = |e: zip::result::ZipError| Error::Archive {
+        path: archive.display().to_string(),
+        message: e.to_string(),
+    };
+    let mut zip = zip::ZipArchive::new(File::open(archive)?).map_err(archive_error)?;
+
+    let family = family_dir_name(family);
+    let dir = options.output_dir.join(&family);
+    fs::create_dir_all(&dir)?;
+
+    let variable_styles: HashSet<FontStyle> = if options.prefer_variable {
+        zip.file_names()
+            .filter_map(|name| Path::new(name).file_name().map(Path::new))
+            .filter(|name| !is_junk(name))
+            .filter_map(|name| Some((font_format(name)?, parse_font_file(name))))
+            .filter(|(format, info)| info.is_variable() && wanted(format, info, options))
+            .map(|(_, info)| info.style)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    
+    let mut fonts = Vec::new();
+    let mut licenses = Vec::new();
+    let mut taken = HashSet::new();
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(archive_error)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Only the base name is used, so entries cannot escape `dir`
+        let Some(name) = entry.enclosed_name().and_then(|p| p.file_name().map(PathBuf::from)) else {
+            continue;
+        };
+        if is_junk(&name) {
+            continue;
+        }
+
+        let is_font;
+        let file_name = if let Some(format) = font_format(&name) {
+            let info = parse_font_file(&name);
+            if !wanted(&format, &info, options) || (!info.is_variable() && variable_styles.contains(&info.style)) {
+                continue;
+            }
+            is_font = true;
+            let preferred = format!("{}-{}.{}", family, info.suffix(), format);
+            // Packages with several widths or subsets would collide on the
+            // short name, so later files keep their original name
+            if taken.contains(&preferred) { name.to_string_lossy().into_owned() } else { preferred }
+        } else if is_license_file(&name) {
+            is_font = false;
+            name.to_string_lossy().into_owned()
+        } else {
+            continue;
+        };
+        if !taken.insert(file_name.clone()) {
+            continue;
+        }
+
+        // Written next to the target and renamed, like downloads
+        let target = dir.join(&file_name);
+        let part = dir.join(format!("{}.part", file_name));
+        io::copy(&mut entry, &mut File::create(&part)?)?;
+        fs::rename(&part, &target)?;
+        if is_font {
+            fonts.push(target);
+        } else {
+            licenses.push(target);
+        }
+    }
+
+    fonts.extend(licenses);
+    Ok(fonts)
+}
+
+fn parse_weight(stem: &str) -> FontWeight {
+    // Numeric weights as used by Google Webfonts Helper (`700italic`)
+    for token in stem.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let digits: String = token.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if digits.len() == 3 && token[3..].chars().all(|c| c.is_ascii_alphabetic()) {
+            if let Ok(n @ 100..=900) = digits.parse::<u16>() {
+                if n % 100 == 0 {
+                    return FontWeight::from_numeric(n);
+                }
+            }
+        }
+    }
+
+    // Longer names first so `extrabold` is not read as `bold`
+    const NAMES: &[(&str, FontWeight)] = &[
+        ("extralight", FontWeight::ExtraLight),
+        ("ultralight", FontWeight::ExtraLight),
+        ("extrabold", FontWeight::ExtraBold),
+        ("ultrabold", FontWeight::ExtraBold),
+        ("semibold", FontWeight::SemiBold),
+        ("demibold", FontWeight::SemiBold),
+        ("hairline", FontWeight::Thin),
+        ("medium", FontWeight::Medium),
+        ("black", FontWeight::Black),
+        ("heavy", FontWeight::Black),
+        ("light", FontWeight::Light),
+        ("thin", FontWeight::Thin),
+        ("bold", FontWeight::Bold),
+    ];
+    let compact: String = stem.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    NAMES.iter()
+        .find(|(name, _)| compact.contains(name))
+        .map(|(_, weight)| *weight)
+        .unwrap_or(FontWeight::Regular)
+}
+
+fn weight_name(weight: FontWeight) -> &'static str {
+    match weight {
+        FontWeight::Thin => "Thin",
+        FontWeight::ExtraLight => "ExtraLight",
+        FontWeight::Light => "Light",
+        FontWeight::Regular => "Regular",
+        FontWeight::Medium => "Medium",
+        FontWeight::SemiBold => "SemiBold",
+        FontWeight::Bold => "Bold",
+        FontWeight::ExtraBold => "ExtraBold",
+        FontWeight::Black => "Black",
+    }
+}
+
+/// Hidden files, such as macOS resource forks (`._Font.ttf`) copied into archives
+fn is_junk(name: &Path) -> bool {
+    name.to_str().is_none_or(|n| n.starts_with('.'))
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_font_file() {
+        let parse = |name: &str| parse_font_file(Path::new(name)).suffix();
+        assert_eq!(parse("Roboto-BoldItalic.ttf"), "BoldItalic");
+        assert_eq!(parse("roboto-v30-latin-700italic.woff2"), "BoldItalic");
+        assert_eq!(parse("roboto-v30-latin-regular.woff2"), "Regular");
+        assert_eq!(parse("Lato-Italic.otf"), "Italic");
+        assert_eq!(parse("OpenSans-ExtraBold.ttf"), "ExtraBold");
+        assert_eq!(parse("Inter[opsz,wght].ttf"), "Variable");
+        assert_eq!(parse("Inter-Italic[opsz,wght].ttf"), "VariableItalic");
+        assert_eq!(family_dir_name("open-sans"), "OpenSans");
+    }
+
+    #[test]
+    fn test_extract_filters_and_lays_out() {
+        let dir = std::env::temp_dir().join(format!("dx-font-archive-{}", std::process::id()));
+        let archive = dir.join("lato.zip");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["Lato/Lato-Regular.ttf", "Lato/Lato-BoldItalic.ttf", "Lato/Lato-Bold.woff", "OFL.txt", "README.md", "__MACOSX/._Lato-Regular.ttf"] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let options = DownloadOptions {
+            output_dir: dir.clone(),
+            formats: vec!["ttf".to_string()],
+            styles: Some(vec![FontStyle::Normal]),
+            ..Default::default()
+        };
+        let files = extract(&archive, "lato", &options).unwrap();
+        let names: Vec<String> = files.iter()
+            .map(|p| p.strip_prefix(&dir).unwrap().display().to_string())
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names, ["Lato/Lato-Regular.ttf", "Lato/OFL.txt"]);
+    }
+}
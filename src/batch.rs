@@ -0,0 +1,165 @@
+//! Download lists
+//!
+//! `dx-font download --from <file>` reads the fonts to fetch from a list. Two
+//! formats are understood:
+//!
+//! - Plain text: one font per line as `font_id` or `provider:font_id`; blank
+//!   lines and `#` comments are ignored.
+//! - JSON: an array whose items are either such strings or objects like
+//!   `{"font_id": "inter", "provider": "fontsource", "weights": [400, 700]}`.
+//!
+//! Anything an entry leaves out comes from the defaults passed in, which the
+//! CLI fills from its own flags and the config.
+
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::models::{DownloadOptions, FontProvider, FontStyle, FontWeight};
+
+/// One font to download
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    pub provider: FontProvider,
+    pub font_id: String,
+    pub options: DownloadOptions,
+}
+
+/// Object form of a JSON list entry
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntryFields {
+    #[serde(alias = "id")]
+    font_id: String,
+    provider: Option<String>,
+    formats: Option<Vec<String>>,
+    weights: Option<Vec<u16>>,
+    italic: Option<bool>,
+    subsets: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Spec(String),
+    Fields(EntryFields),
+}
+
+/// Read a download list, choosing the format by extension (`.json` or text)
+pub fn read_list(path: &Path, defaults: &DownloadRequest) -> Result<Vec<DownloadRequest>> {
+    let text = std::fs::read_to_string(path)?;
+    let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    if is_json {
+        parse_json(&text, defaults)
+    } else {
+        parse_text(&text, defaults)
+    }
+}
+
+/// Parse the plain text list format
+pub fn parse_text(text: &str, defaults: &DownloadRequest) -> Result<Vec<DownloadRequest>> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        })
+        .map(|(line_no, line)| {
+            parse_spec(line, defaults).map_err(|message| invalid(format!("line {}", line_no), message))
+        })
+        .collect()
+}
+
+/// Parse the JSON list format
+pub fn parse_json(text: &str, defaults: &DownloadRequest) -> Result<Vec<DownloadRequest>> {
+    let entries: Vec<Entry> = serde_json::from_str(text)
+        .map_err(|e| invalid(format!("line {}", e.line()), e.to_string()))?;
+
+    entries.into_iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let item = format!("item {}", i + 1);
+            match entry {
+                Entry::Spec(spec) => parse_spec(&spec, defaults).map_err(|message| invalid(item, message)),
+                Entry::Fields(fields) => from_fields(fields, defaults).map_err(|message| invalid(item, message)),
+            }
+        })
+        .collect()
+}
+
+/// `font_id` or `provider:font_id`
+fn parse_spec(spec: &str, defaults: &DownloadRequest) -> std::result::Result<DownloadRequest, String> {
+    let (provider, font_id) = match spec.split_once(':') {
+        Some((provider, font_id)) => (provider.trim().parse()?, font_id.trim()),
+        None => (defaults.provider.clone(), spec.trim()),
+    };
+    if font_id.is_empty() || font_id.contains(char::is_whitespace) {
+        return Err(format!("expected `font_id` or `provider:font_id`, got '{}'", spec));
+    }
+    Ok(DownloadRequest {
+        provider,
+        font_id: font_id.to_string(),
+        options: defaults.options.clone(),
+    })
+}
+
+fn from_fields(fields: EntryFields, defaults: &DownloadRequest) -> std::result::Result<DownloadRequest, String> {
+    let mut options = defaults.options.clone();
+    if let Some(formats) = fields.formats {
+        options.formats = formats;
+    }
+    if let Some(weights) = fields.weights {
+        options.weights = Some(weights.into_iter().map(FontWeight::from_numeric).collect());
+    }
+    if let Some(italic) = fields.italic {
+        options.styles = italic.then(|| vec![FontStyle::Italic]);
+    }
+    if let Some(subsets) = fields.subsets {
+        options.subsets = subsets;
+    }
+
+    Ok(DownloadRequest {
+        provider: match fields.provider {
+            Some(name) => name.parse()?,
+            None => defaults.provider.clone(),
+        },
+        font_id: fields.font_id,
+        options,
+    })
+}
+
+fn invalid(location: String, message: String) -> Error {
+    Error::InvalidList { location, message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> DownloadRequest {
+        DownloadRequest {
+            provider: FontProvider::GoogleFonts,
+            font_id: String::new(),
+            options: DownloadOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        let text = "# sans\ninter\nfontsource:fira-code  # mono\n\n";
+        let requests = parse_text(text, &defaults()).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].provider, FontProvider::GoogleFonts);
+        assert_eq!(requests[1].provider, FontProvider::Fontsource);
+        assert_eq!(requests[1].font_id, "fira-code");
+
+        let json = r#"["lato", {"id": "inter", "provider": "bunny", "weights": [400, 700], "italic": true}]"#;
+        let requests = parse_json(json, &defaults()).unwrap();
+        assert_eq!(requests[1].provider, FontProvider::BunnyFonts);
+        assert_eq!(requests[1].options.weights, Some(vec![FontWeight::Regular, FontWeight::Bold]));
+        assert_eq!(requests[1].options.styles, Some(vec![FontStyle::Italic]));
+
+        let err = parse_text("inter\nnope:lato\n", &defaults()).unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+    }
+}
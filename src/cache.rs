@@ -0,0 +1,505 @@
+//! On-disk catalog cache
+//!
+//! Keeps each provider's catalog (the result of `list_all`) and every font
+//! family looked up through `get_font_family` as JSON files under
+//! `Config::cache_dir` and serves them from there until
+//! `Config::cache_ttl_seconds` expires. The same files double as the local
+//! snapshot used by [`CachePolicy::Offline`]. Wrap a registry with
+//! [`ProviderRegistry::with_cache`](crate::providers::ProviderRegistry::with_cache)
+//! to put every provider behind the cache.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::fs;
+use tokio::task::JoinSet;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::models::{Font, FontFamily, FontProvider, SearchQuery};
+use crate::providers::FontProviderTrait;
+
+/// How cached catalogs are used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Serve fresh entries from disk, refetch expired ones
+    #[default]
+    Normal,
+    /// Ignore cached entries and always refetch (the result is still stored)
+    Refresh,
+    /// Serve any cached entry immediately and refresh expired ones in the background
+    StaleWhileRevalidate,
+    /// Never touch the network; fail when the snapshot has no data
+    Offline,
+}
+
+/// A provider catalog as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCatalog {
+    pub provider: String,
+    /// Unix timestamp (seconds) of when the catalog was fetched
+    pub fetched_at: u64,
+    pub fonts: Vec<Font>,
+}
+
+impl CachedCatalog {
+    /// Age of this catalog
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched_at))
+    }
+
+    /// Whether this catalog is younger than `ttl`
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        self.age() < ttl
+    }
+}
+
+/// Catalog cache rooted at a directory
+#[derive(Debug, Clone)]
+pub struct CatalogCache {
+    dir: PathBuf,
+    ttl: Duration,
+    policy: CachePolicy,
+    /// Background refreshes started by [`CachePolicy::StaleWhileRevalidate`]
+    refreshes: Arc<std::sync::Mutex<JoinSet<()>>>,
+}
+
+impl CatalogCache {
+    /// Create a cache in `dir` with the given TTL
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            policy: CachePolicy::Normal,
+            refreshes: Arc::default(),
+        }
+    }
+
+    /// Create a cache from `Config::cache_dir` and `Config::cache_ttl_seconds`
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.cache_dir, Duration::from_secs(config.cache_ttl_seconds))
+    }
+
+    /// Set the cache policy
+    pub fn with_policy(mut self, policy: CachePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Wait up to `limit` for background refreshes, then cancel the rest
+    ///
+    /// Refreshes are tasks on the current runtime, so a short-lived process
+    /// should call this before it exits or the fresh catalogs are never written.
+    pub async fn wait_for_refreshes(&self, limit: Duration) {
+        let mut refreshes = std::mem::take(&mut *self.refreshes.lock().expect("refresh set lock"));
+        let all_done = async { while refreshes.join_next().await.is_some() {} };
+        if tokio::time::timeout(limit, all_done).await.is_err() {
+            tracing::debug!("Cancelling {} unfinished catalog refreshes", refreshes.len());
+        }
+    }
+
+    /// Path of the catalog file for a provider
+    pub fn catalog_path(&self, provider: &str) -> PathBuf {
+        self.dir.join("catalogs").join(format!("{}.json", slugify(provider)))
+    }
+
+    /// Path of the cached details for one font family
+    pub fn family_path(&self, provider: &str, font_id: &str) -> PathBuf {
+        self.dir
+            .join("families")
+            .join(slugify(provider))
+            .join(format!("{}.json", slugify(font_id)))
+    }
+
+    /// Load a provider's catalog regardless of its age
+    pub async fn load(&self, provider: &str) -> Option<CachedCatalog> {
+        read_json(&self.catalog_path(provider)).await
+    }
+
+    /// Store a provider's catalog, replacing any previous entry atomically
+    pub async fn store(&self, provider: &str, fonts: &[Font]) -> Result<()> {
+        let catalog = CachedCatalog {
+            provider: provider.to_string(),
+            fetched_at: unix_now(),
+            fonts: fonts.to_vec(),
+        };
+        write_json(&self.catalog_path(provider), &catalog).await
+    }
+
+    /// Load a cached font family regardless of its age
+    pub async fn load_family(&self, provider: &str, font_id: &str) -> Option<CachedFamily> {
+        read_json(&self.family_path(provider, font_id)).await
+    }
+
+    /// Store a font family's details
+    pub async fn store_family(&self, provider: &str, family: &FontFamily) -> Result<()> {
+        let cached = CachedFamily {
+            fetched_at: unix_now(),
+            family: family.clone(),
+        };
+        write_json(&self.family_path(provider, &family.id), &cached).await
+    }
+
+    /// Remove every cached catalog and font family
+    pub async fn clear(&self) -> Result<()> {
+        for sub in ["catalogs", "families"] {
+            let path = self.dir.join(sub);
+            if fs::try_exists(&path).await? {
+                fs::remove_dir_all(&path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A font family as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFamily {
+    /// Unix timestamp (seconds) of when the family was fetched
+    pub fetched_at: u64,
+    pub family: FontFamily,
+}
+
+impl CachedFamily {
+    /// Whether these details are younger than `ttl`
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched_at)) < ttl
+    }
+}
+
+/// A provider whose catalog is served through a [`CatalogCache`]
+pub struct CachedProvider {
+    inner: Arc<dyn FontProviderTrait>,
+    cache: Arc<CatalogCache>,
+}
+
+impl CachedProvider {
+    pub fn new(inner: Arc<dyn FontProviderTrait>, cache: Arc<CatalogCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    /// Fetch the catalog from the provider and store it
+    async fn fetch_and_store(
+        inner: &Arc<dyn FontProviderTrait>,
+        cache: &CatalogCache,
+    ) -> Result<Vec<Font>> {
+        let fonts = inner.list_all().await?;
+        // An empty catalog usually means the provider is having a bad day;
+        // don't let it replace a good one.
+        if !fonts.is_empty() {
+            if let Err(e) = cache.store(inner.name(), &fonts).await {
+                tracing::warn!("Failed to cache catalog for {}: {}", inner.name(), e);
+            }
+        }
+        Ok(fonts)
+    }
+
+    fn spawn_revalidate(&self) {
+        let inner = Arc::clone(&self.inner);
+        let cache = Arc::clone(&self.cache);
+        self.cache.refreshes.lock().expect("refresh set lock").spawn(async move {
+            if let Err(e) = Self::fetch_and_store(&inner, &cache).await {
+                tracing::debug!("Background refresh of {} failed: {}", inner.name(), e);
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl FontProviderTrait for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    fn provider(&self) -> FontProvider {
+        self.inner.provider()
+    }
+
+    fn has_full_catalog(&self) -> bool {
+        self.inner.has_full_catalog()
+    }
+
+    /// Search results are not cached; providers without a full catalog are
+    /// asked directly, except offline, where the snapshot is all there is
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Font>> {
+        if !self.inner.has_full_catalog() && self.cache.policy() != CachePolicy::Offline {
+            return self.inner.search(query).await;
+        }
+        let fonts = self.list_all().await?;
+
+        let query_lower = query.query.to_lowercase();
+        let filtered: Vec<Font> = fonts
+            .into_iter()
+            .filter(|f| f.name.to_lowercase().contains(&query_lower))
+            .collect();
+
+        Ok(filtered)
+    }
+
+    async fn list_all(&self) -> Result<Vec<Font>> {
+        let name = self.inner.name();
+
+        match self.cache.policy() {
+            CachePolicy::Refresh => Self::fetch_and_store(&self.inner, &self.cache).await,
+            CachePolicy::Normal => {
+                let cached = self.cache.load(name).await;
+                if let Some(catalog) = &cached {
+                    if catalog.is_fresh(self.cache.ttl()) {
+                        tracing::debug!("Serving {} from cache ({} fonts)", name, catalog.fonts.len());
+                        return Ok(catalog.fonts.clone());
+                    }
+                }
+
+                match Self::fetch_and_store(&self.inner, &self.cache).await {
+                    Ok(fonts) => Ok(fonts),
+                    Err(e) => match cached {
+                        Some(catalog) => {
+                            tracing::warn!("Refreshing {} failed ({}), serving stale catalog", name, e);
+                            Ok(catalog.fonts)
+                        }
+                        None => Err(e),
+                    },
+                }
+            }
+            CachePolicy::StaleWhileRevalidate => match self.cache.load(name).await {
+                Some(catalog) => {
+                    if !catalog.is_fresh(self.cache.ttl()) {
+                        self.spawn_revalidate();
+                    }
+                    Ok(catalog.fonts)
+                }
+                None => Self::fetch_and_store(&self.inner, &self.cache).await,
+            },
+            CachePolicy::Offline => match self.cache.load(name).await {
+                Some(catalog) => Ok(catalog.fonts),
+                None => Err(Error::Offline(format!(
+                    "No offline catalog for {} (expected {})",
+                    name,
+                    self.cache.catalog_path(name).display()
+                ))),
+            },
+        }
+    }
+
+    async fn get_font_family(&self, font_id: &str) -> Result<FontFamily> {
+        let name = self.inner.name();
+        let cached = self.cache.load_family(name, font_id).await;
+
+        match self.cache.policy() {
+            CachePolicy::Offline => {
+                return cached.map(|c| c.family).ok_or_else(|| Error::Offline(format!(
+                    "Font '{}' from {} is not in the offline snapshot (expected {})",
+                    font_id,
+                    name,
+                    self.cache.family_path(name, font_id).display()
+                )));
+            }
+            CachePolicy::Normal | CachePolicy::StaleWhileRevalidate => {
+                if let Some(c) = &cached {
+                    if c.is_fresh(self.cache.ttl()) {
+                        return Ok(c.family.clone());
+                    }
+                }
+            }
+            CachePolicy::Refresh => {}
+        }
+
+        match self.inner.get_font_family(font_id).await {
+            Ok(family) => {
+                if let Err(e) = self.cache.store_family(name, &family).await {
+                    tracing::warn!("Failed to cache {} from {}: {}", font_id, name, e);
+                }
+                Ok(family)
+            }
+            Err(e) => match cached {
+                Some(c) if self.cache.policy() != CachePolicy::Refresh => {
+                    tracing::warn!("Fetching {} from {} failed ({}), serving stale details", font_id, name, e);
+                    Ok(c.family)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    async fn get_download_url(&self, font_id: &str) -> Result<String> {
+        if self.cache.policy() == CachePolicy::Offline {
+            return Err(Error::Offline(format!("Cannot resolve download URLs for {} in offline mode", font_id)));
+        }
+        self.inner.get_download_url(font_id).await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        if self.cache.policy() == CachePolicy::Offline {
+            // Offline, a provider is usable exactly when its snapshot is present
+            return Ok(self.cache.load(self.inner.name()).await.is_some());
+        }
+        self.inner.health_check().await
+    }
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+    let data = fs::read(path).await.ok()?;
+    match serde_json::from_slice(&data) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Ignoring corrupt cache file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write `value` as JSON to `path` via a temporary file and rename
+async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await
+            .with_context(|| format!("Failed to create cache directory {}", parent.display()))?;
+    }
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec(value)?).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Turn a provider name into a file-system friendly slug ("1001 Fonts" -> "1001-fonts")
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl FontProviderTrait for CountingProvider {
+        fn name(&self) -> &str { "Counting Fonts" }
+        fn base_url(&self) -> &str { "https://example.com" }
+        async fn search(&self, _query: &SearchQuery) -> Result<Vec<Font>> { self.list_all().await }
+        async fn list_all(&self) -> Result<Vec<Font>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![Font::test("Inter", FontProvider::Custom("Counting Fonts".to_string()))])
+        }
+        async fn get_font_family(&self, font_id: &str) -> Result<FontFamily> { Err(Error::font_not_found(self.name(), font_id)) }
+        async fn get_download_url(&self, font_id: &str) -> Result<String> { Err(Error::font_not_found(self.name(), font_id)) }
+        async fn health_check(&self) -> Result<bool> { Ok(true) }
+    }
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dx-font-cache-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("1001 Fonts"), "1001-fonts");
+        assert_eq!(slugify("Uncut.wtf"), "uncut-wtf");
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_serves_from_disk() {
+        let dir = temp_cache_dir("serve");
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0) });
+        let cache = Arc::new(CatalogCache::new(&dir, Duration::from_secs(3600)));
+        let provider = CachedProvider::new(inner.clone(), cache.clone());
+
+        assert_eq!(provider.list_all().await.unwrap().len(), 1);
+        assert_eq!(provider.list_all().await.unwrap().len(), 1);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+        assert!(cache.catalog_path("Counting Fonts").exists());
+
+        let refreshing = CachedProvider::new(
+            inner.clone(),
+            Arc::new(CatalogCache::new(&dir, Duration::from_secs(3600)).with_policy(CachePolicy::Refresh)),
+        );
+        refreshing.list_all().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_refetched() {
+        let dir = temp_cache_dir("expired");
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0) });
+        let provider = CachedProvider::new(inner.clone(), Arc::new(CatalogCache::new(&dir, Duration::ZERO)));
+
+        provider.list_all().await.unwrap();
+        provider.list_all().await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_offline_requires_snapshot() {
+        let dir = temp_cache_dir("offline");
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0) });
+        let offline = CachedProvider::new(
+            inner.clone(),
+            Arc::new(CatalogCache::new(&dir, Duration::ZERO).with_policy(CachePolicy::Offline)),
+        );
+
+        assert!(offline.list_all().await.is_err());
+        assert!(offline.get_font_family("inter").await.is_err());
+
+        let online = CachedProvider::new(inner.clone(), Arc::new(CatalogCache::new(&dir, Duration::ZERO)));
+        online.list_all().await.unwrap();
+
+        // An expired snapshot is still served offline, without calling the provider
+        assert_eq!(offline.list_all().await.unwrap().len(), 1);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate_refresh_can_be_awaited() {
+        let dir = temp_cache_dir("swr");
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0) });
+        CachedProvider::new(inner.clone(), Arc::new(CatalogCache::new(&dir, Duration::ZERO))).list_all().await.unwrap();
+
+        let cache = Arc::new(CatalogCache::new(&dir, Duration::ZERO).with_policy(CachePolicy::StaleWhileRevalidate));
+        let swr = CachedProvider::new(inner.clone(), cache.clone());
+        assert_eq!(swr.list_all().await.unwrap().len(), 1);
+        cache.wait_for_refreshes(Duration::from_secs(5)).await;
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -83,7 +83,7 @@ impl CdnUrlGenerator {
     }
     
     /// Generate CDN URLs for a Bunny Fonts font
-    pub fn for_bunny_font(font_id: &str, font_name: &str) -> FontCdnUrls {
+    pub fn for_bunny_font(_font_id: &str, font_name: &str) -> FontCdnUrls {
         let font_family = font_name.replace(' ', "+");
         
         FontCdnUrls {
@@ -2,7 +2,7 @@
 //!
 //! Provides command-line interface for searching and downloading fonts.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 /// dx-font - Access 50k+ commercial-free fonts
@@ -18,6 +18,34 @@ pub struct Cli {
     #[arg(short, long, global = true, default_value = "table")]
     pub format: OutputFormat,
     
+    /// Ignore cached provider catalogs and fetch them again
+    #[arg(long, global = true, conflicts_with_all = ["stale_while_revalidate", "offline"])]
+    pub refresh: bool,
+    
+    /// Serve expired catalogs immediately and refresh them in the background
+    #[arg(long, global = true, conflicts_with = "offline")]
+    pub stale_while_revalidate: bool,
+    
+    /// Run only from the local catalog snapshot, without network access
+    ///
+    /// `cache update` snapshots provider catalogs only. Family details
+    /// (`info`) are available for families looked up online before, and
+    /// download, install and sync always fail because they need the network.
+    #[arg(long, global = true)]
+    pub offline: bool,
+    
+    /// Exit with an error if any provider fails or times out
+    #[arg(long, global = true)]
+    pub strict: bool,
+    
+    /// Configuration file to use instead of the project-local one
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    
+    /// Override a configuration value (repeatable)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub overrides: Vec<(String, String)>,
+    
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -33,50 +61,145 @@ pub enum Commands {
         #[arg(short, long, default_value = "20")]
         limit: usize,
         
-        /// Filter by provider (google, bunny, fontsource, fontshare)
+        /// Filter by provider (google, bunny, fontsource, dafont, ...); comma-separated, `!name` excludes
         #[arg(short, long)]
-        provider: Option<String>,
+        provider: Vec<String>,
         
-        /// Filter by category (serif, sans-serif, display, handwriting, monospace)
+        /// Filter by category (serif, sans, display, handwriting, mono); comma-separated, `!name` excludes
         #[arg(short, long)]
-        category: Option<String>,
+        category: Vec<String>,
+        
+        /// Filter by license (ofl, apache, mit, gpl, public-domain, free-commercial, commercial-ok); comma-separated, `!name` excludes
+        #[arg(long)]
+        license: Vec<String>,
+        
+        /// Show one row per provider instead of merging the same family across providers
+        #[arg(long)]
+        all_sources: bool,
+        
+        /// Print each provider's matches as soon as it answers (no merging or paging)
+        #[arg(long, conflicts_with_all = ["all_sources", "page", "offset", "cursor"])]
+        stream: bool,
+        
+        #[command(flatten)]
+        paging: PageArgs,
     },
     
     /// Download a font
     Download {
         /// Font ID or name
-        font_id: String,
+        #[arg(required_unless_present = "from", conflicts_with = "from")]
+        font_id: Option<String>,
         
-        /// Provider to download from
+        /// Provider to download from (default for list entries without one)
         #[arg(short, long, default_value = "google")]
         provider: String,
         
-        /// Output directory
-        #[arg(short, long, default_value = "./fonts")]
-        output: PathBuf,
+        /// Download every font in a list file (`.txt`: `[provider:]font_id` per line, or `.json`)
+        #[arg(long, value_name = "FILE")]
+        from: Option<PathBuf>,
+        
+        /// Write a JSON report of every download to this file
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
         
-        /// Font formats to download (ttf, woff, woff2, otf)
-        #[arg(short = 'F', long, default_values = ["ttf", "woff2"])]
+        /// Output directory (defaults to `output_dir` from the config)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Font formats to download (ttf, woff, woff2, otf; defaults to `preferred_formats`)
+        #[arg(short = 'F', long)]
         formats: Vec<String>,
         
         /// Font subsets to download (latin, cyrillic, greek, etc.)
         #[arg(short = 'S', long, default_values = ["latin"])]
         subsets: Vec<String>,
+        
+        /// Weights to download, e.g. `--weights 400,700` (all by default)
+        #[arg(short = 'W', long, value_delimiter = ',')]
+        weights: Vec<u16>,
+        
+        /// Only download italic styles (normal and italic by default)
+        #[arg(long)]
+        italic: bool,
+        
+        /// Prefer a family's variable font over its static instances
+        #[arg(long)]
+        variable: bool,
+        
+        /// Download again even if the files are already present
+        #[arg(long)]
+        force: bool,
+        
+        #[command(flatten)]
+        css: CssArgs,
+    },
+    
+    /// Download the fonts declared in a project manifest and pin them in a lockfile
+    Sync {
+        /// Manifest file; the lockfile is written next to it with a `.lock` extension
+        #[arg(short, long, default_value = "dx-font.toml")]
+        manifest: PathBuf,
+        
+        /// Fail instead of updating the lockfile if the result differs from it
+        #[arg(long)]
+        locked: bool,
+        
+        /// Download again even if the files are already present
+        #[arg(long)]
+        force: bool,
+        
+        #[command(flatten)]
+        css: CssArgs,
+    },
+    
+    /// Install a font family for the current user (Linux)
+    Install {
+        /// Font ID or name
+        font_id: String,
+        
+        /// Provider to download from
+        #[arg(short, long, default_value = "google")]
+        provider: String,
+        
+        /// Download again even if the files are already installed
+        #[arg(long)]
+        force: bool,
+    },
+    
+    /// Remove a font family installed with `install`
+    Uninstall {
+        /// Font ID, family name or directory name
+        font: String,
     },
     
+    /// List font families installed with `install`
+    Installed,
+    
     /// List all available fonts
     List {
-        /// Provider to list from (google, bunny, fontsource, fontshare, all)
-        #[arg(short, long, default_value = "all")]
-        provider: String,
+        /// Provider to list from (google, bunny, fontsource, ...; all by default); `!name` excludes
+        #[arg(short, long)]
+        provider: Vec<String>,
         
         /// Limit number of results
         #[arg(short, long)]
         limit: Option<usize>,
         
-        /// Filter by category
+        /// Filter by category; comma-separated, `!name` excludes
         #[arg(short, long)]
-        category: Option<String>,
+        category: Vec<String>,
+        
+        /// Filter by license (`commercial-ok`: confirmed free for commercial use); comma-separated, `!name` excludes
+        #[arg(long)]
+        license: Vec<String>,
+        
+        /// Show one row per provider instead of merging the same family across providers
+        #[arg(long)]
+        all_sources: bool,
+        
+        #[command(flatten)]
+        paging: PageArgs,
     },
     
     /// Get detailed information about a font
@@ -89,6 +212,36 @@ pub enum Commands {
         provider: String,
     },
     
+    /// Read the metadata of a font file (TTF, OTF, WOFF or WOFF2)
+    Inspect {
+        /// Font files to inspect
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    
+    /// Write the license texts of a font set to `FONT-LICENSES.md`, JSON or SPDX
+    Licenses {
+        /// Directory of downloaded fonts (defaults to `output_dir` from the config)
+        #[arg(conflicts_with_all = ["manifest", "installed"])]
+        path: Option<PathBuf>,
+        
+        /// Report the fonts pinned in this manifest's lockfile
+        #[arg(short, long, value_name = "FILE", conflicts_with = "installed")]
+        manifest: Option<PathBuf>,
+        
+        /// Report the fonts installed with `install`
+        #[arg(long)]
+        installed: bool,
+        
+        /// Report format
+        #[arg(long = "as", value_name = "FORMAT", default_value = "md")]
+        report: LicenseReport,
+        
+        /// Output file (defaults to `FONT-LICENSES.md`, `.json` or `.spdx`)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    
     /// Show font statistics
     Stats,
     
@@ -97,6 +250,58 @@ pub enum Commands {
     
     /// Show available providers and their font counts
     Providers,
+    
+    /// Manage the local catalog cache used for offline mode
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    
+    /// Inspect and edit the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the effective configuration and the files it came from
+    Show,
+    
+    /// Print a single configuration value
+    Get {
+        /// Configuration key (e.g. output_dir)
+        key: String,
+    },
+    
+    /// Set a configuration value in the user (or project) config file
+    Set {
+        /// Configuration key (e.g. max_concurrent_downloads)
+        key: String,
+        
+        /// New value; lists are comma-separated
+        value: String,
+        
+        /// Write to the project-local `.dx-font.toml` instead of the user file
+        #[arg(long)]
+        project: bool,
+    },
+    
+    /// Print the configuration file locations
+    Path,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Show which provider catalogs are cached and how old they are
+    Status,
+    
+    /// Fetch every provider catalog and store it as the offline snapshot
+    Update,
+    
+    /// Remove all cached catalogs and font details
+    Clear,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -106,6 +311,123 @@ pub enum OutputFormat {
     Simple,
 }
 
+/// Formats of the `licenses` report
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum LicenseReport {
+    Md,
+    Json,
+    Spdx,
+}
+
+impl LicenseReport {
+    /// Default report file name
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            LicenseReport::Md => "FONT-LICENSES.md",
+            LicenseReport::Json => "FONT-LICENSES.json",
+            LicenseReport::Spdx => "FONT-LICENSES.spdx",
+        }
+    }
+}
+
+/// Paging flags shared by `search` and `list`
+#[derive(Args, Debug, Clone, Default)]
+pub struct PageArgs {
+    /// Page number, starting at 1 (pages are `--limit` rows long)
+    #[arg(long, conflicts_with_all = ["offset", "cursor"])]
+    pub page: Option<usize>,
+    
+    /// Number of rows to skip
+    #[arg(long, conflicts_with = "cursor")]
+    pub offset: Option<usize>,
+    
+    /// Continue from the cursor printed after a previous page
+    #[arg(long)]
+    pub cursor: Option<String>,
+}
+
+/// Stylesheet flags shared by `download` and `sync`
+#[derive(Args, Debug, Clone, Default)]
+pub struct CssArgs {
+    /// Write an `@font-face` stylesheet for the downloaded files
+    #[arg(long, value_name = "FILE")]
+    pub css: Option<PathBuf>,
+    
+    /// URL prefix the output directory is served under (defaults to `css.public_url`)
+    #[arg(long, value_name = "URL", requires = "css")]
+    pub public_url: Option<String>,
+    
+    /// Also emit `--font-<id>` custom properties with a fallback stack
+    #[arg(long, requires = "css")]
+    pub css_vars: bool,
+}
+
+impl CssArgs {
+    /// The configured stylesheet settings with these flags applied
+    pub fn apply(&self, config: &crate::config::CssConfig) -> crate::config::CssConfig {
+        let mut config = config.clone();
+        if let Some(url) = &self.public_url {
+            config.public_url = url.clone();
+        }
+        config.custom_properties |= self.css_vars;
+        config
+    }
+}
+
+impl PageArgs {
+    /// Apply these flags and the page size to a query
+    pub fn apply(&self, query: &mut crate::models::SearchQuery, limit: Option<usize>) -> anyhow::Result<()> {
+        query.limit = limit;
+        query.cursor = self.cursor.clone();
+        query.offset = match self.page {
+            Some(0) => anyhow::bail!("--page starts at 1"),
+            Some(page) => {
+                let size = limit.ok_or_else(|| anyhow::anyhow!("--page needs --limit to know the page size"))?;
+                Some((page - 1) * size)
+            }
+            None => self.offset,
+        };
+        Ok(())
+    }
+}
+
+impl Cli {
+    /// Load the layered configuration with this invocation's overrides applied
+    pub fn load_config(&self) -> anyhow::Result<crate::config::LoadedConfig> {
+        let mut loader = crate::config::ConfigLoader::new();
+        if let Some(path) = &self.config {
+            loader = loader.file(path);
+        }
+        for (key, value) in &self.overrides {
+            loader = loader.set(key, value);
+        }
+        if self.offline {
+            loader = loader.set("offline", "true");
+        }
+        loader.load()
+    }
+    
+    /// Cache policy selected by the global cache flags
+    pub fn cache_policy(&self) -> crate::cache::CachePolicy {
+        use crate::cache::CachePolicy;
+        if self.offline {
+            CachePolicy::Offline
+        } else if self.refresh {
+            CachePolicy::Refresh
+        } else if self.stale_while_revalidate {
+            CachePolicy::StaleWhileRevalidate
+        } else {
+            CachePolicy::Normal
+        }
+    }
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", s))
+}
+
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
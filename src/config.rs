@@ -1,10 +1,30 @@
 //! Configuration for dx-font
+//!
+//! Configuration is layered, later layers overriding earlier ones:
+//!
+//! 1. Built-in defaults
+//! 2. User file: `$XDG_CONFIG_HOME/dx-font/config.toml`
+//! 3. Project file: `.dx-font.toml` or `.dx-font.json` in the current directory
+//!    (replaced by an explicit `--config <file>`)
+//! 4. `DX_FONT_*` environment variables (e.g. `DX_FONT_OUTPUT_DIR`)
+//! 5. CLI overrides (`--set key=value` and command flags)
 
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::Value;
+use crate::models::FontProvider;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable prefix for configuration overrides
+pub const ENV_PREFIX: &str = "DX_FONT_";
+
+/// Project-local configuration file names, in lookup order
+pub const PROJECT_CONFIG_FILES: &[&str] = &[".dx-font.toml", ".dx-font.json"];
 
 /// Main configuration struct
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Default output directory for downloaded fonts
     pub output_dir: PathBuf,
@@ -18,6 +38,9 @@ pub struct Config {
     /// Request timeout in seconds
     pub timeout_seconds: u64,
     
+    /// Connection timeout in seconds
+    pub connect_timeout_seconds: u64,
+    
     /// User agent for HTTP requests
     pub user_agent: String,
     
@@ -26,6 +49,106 @@ pub struct Config {
     
     /// Cache TTL in seconds
     pub cache_ttl_seconds: u64,
+    
+    /// Run only from the local catalog snapshot
+    pub offline: bool,
+    
+    /// Providers in the order their copy of a family is preferred
+    pub provider_priority: Vec<String>,
+    
+    /// Local clone or tarball (`.tar.gz`) of the google/fonts repository,
+    /// read for authoritative family metadata
+    pub google_fonts_repo: Option<PathBuf>,
+    
+    /// Retry and rate limit settings for every HTTP request
+    pub http: HttpConfig,
+    
+    /// Stylesheet generation for self-hosted fonts
+    pub css: CssConfig,
+}
+
+/// `@font-face` stylesheet settings (`[css]` table)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CssConfig {
+    /// URL prefix the output directory is served under (`/fonts`, `https://cdn.example.com/fonts`)
+    pub public_url: String,
+    
+    /// `font-display` value for every rule
+    pub font_display: String,
+    
+    /// Also emit a `--font-<id>` custom property per family with a fallback stack
+    pub custom_properties: bool,
+}
+
+impl Default for CssConfig {
+    fn default() -> Self {
+        Self {
+            public_url: "/fonts".to_string(),
+            font_display: "swap".to_string(),
+            custom_properties: false,
+        }
+    }
+}
+
+/// Retry and per-host rate limit settings (`[http]` table)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Retries after the first attempt for connect errors, 429 and 5xx
+    pub max_retries: u32,
+    
+    /// Delay before the first retry in milliseconds, doubled on each attempt
+    pub retry_base_ms: u64,
+    
+    /// Upper bound for a single retry delay, including `Retry-After`
+    pub retry_max_ms: u64,
+    
+    /// Requests in flight per host
+    pub max_per_host: usize,
+    
+    /// Requests started per second per host (0 = unlimited)
+    pub requests_per_second: u32,
+    
+    /// Overrides for individual hosts, keyed by host name
+    pub hosts: BTreeMap<String, HostLimits>,
+}
+
+/// Limits for one host; unset fields fall back to the `[http]` defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostLimits {
+    pub max_per_host: Option<usize>,
+    pub requests_per_second: Option<u32>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        // Scraped sites throttle bursty clients, so they get gentler limits
+        let scraped = HostLimits { max_per_host: Some(2), requests_per_second: Some(2) };
+        Self {
+            max_retries: 3,
+            retry_base_ms: 500,
+            retry_max_ms: 30_000,
+            max_per_host: 8,
+            requests_per_second: 0,
+            hosts: ["www.dafont.com", "www.fontspace.com", "www.1001fonts.com", "www.fontsquirrel.com"]
+                .into_iter()
+                .map(|host| (host.to_string(), scraped.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Effective `(max_per_host, requests_per_second)` for `host`
+    pub fn limits_for(&self, host: &str) -> (usize, u32) {
+        let limits = self.hosts.get(host);
+        (
+            limits.and_then(|l| l.max_per_host).unwrap_or(self.max_per_host).max(1),
+            limits.and_then(|l| l.requests_per_second).unwrap_or(self.requests_per_second),
+        )
+    }
 }
 
 impl Default for Config {
@@ -40,11 +163,300 @@ impl Default for Config {
             ],
             max_concurrent_downloads: 5,
             timeout_seconds: 30,
+            connect_timeout_seconds: 10,
             user_agent: format!("dx-font/{}", env!("CARGO_PKG_VERSION")),
             cache_dir: dirs::cache_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
                 .join("dx-font"),
             cache_ttl_seconds: 3600, // 1 hour
+            offline: false,
+            provider_priority: crate::dedup::default_priority()
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect(),
+            google_fonts_repo: None,
+            http: HttpConfig::default(),
+            css: CssConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the layered configuration (files and environment, no CLI overrides)
+    pub fn load() -> Result<Self> {
+        Ok(ConfigLoader::new().load()?.config)
+    }
+    
+    /// Path of the user configuration file
+    pub fn user_config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("dx-font").join("config.toml"))
+    }
+    
+    /// First project configuration file found in `dir`
+    pub fn project_config_path(dir: &Path) -> Option<PathBuf> {
+        PROJECT_CONFIG_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| p.is_file())
+    }
+    
+    /// Parsed `provider_priority`; unknown names are skipped with a warning
+    pub fn priority_order(&self) -> Vec<FontProvider> {
+        self.provider_priority
+            .iter()
+            .filter_map(|name| match name.parse() {
+                Ok(provider) => Some(provider),
+                Err(e) => {
+                    tracing::warn!("Ignoring provider_priority entry: {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+    
+    /// Look up a value by dotted key
+    pub fn get(&self, key: &str) -> Result<Value> {
+        let value = serde_json::to_value(self)?;
+        lookup(&value, key)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown config key: {}", key))
+    }
+}
+
+/// A loaded configuration and the files it was read from
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: Config,
+    pub files: Vec<PathBuf>,
+}
+
+/// Builds a [`Config`] from defaults, files, environment and overrides
+#[derive(Debug, Clone)]
+pub struct ConfigLoader {
+    user_file: Option<PathBuf>,
+    project_dir: Option<PathBuf>,
+    explicit_file: Option<PathBuf>,
+    env: Option<Vec<(String, String)>>,
+    overrides: Vec<(String, String)>,
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigLoader {
+    /// Loader using the standard file locations and process environment
+    pub fn new() -> Self {
+        Self {
+            user_file: Config::user_config_path(),
+            project_dir: std::env::current_dir().ok(),
+            explicit_file: None,
+            env: None,
+            overrides: Vec::new(),
+        }
+    }
+    
+    /// Loader that only reads what is explicitly added (useful for tests)
+    pub fn empty() -> Self {
+        Self {
+            user_file: None,
+            project_dir: None,
+            explicit_file: None,
+            env: Some(Vec::new()),
+            overrides: Vec::new(),
+        }
+    }
+    
+    /// Use this file instead of the project-local one
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.explicit_file = Some(path.into());
+        self
+    }
+    
+    /// Use these variables instead of the process environment
+    pub fn env<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Some(vars.into_iter().map(|(k, v)| (k.into(), v.into())).collect());
+        self
+    }
+    
+    /// Add a CLI override (`key=value`)
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+    
+    pub fn load(&self) -> Result<LoadedConfig> {
+        let mut value = serde_json::to_value(Config::default())?;
+        let mut files = Vec::new();
+        
+        if let Some(path) = self.user_file.as_ref().filter(|p| p.is_file()) {
+            merge(&mut value, read_file(path)?);
+            files.push(path.clone());
+        }
+        
+        let project_file = match &self.explicit_file {
+            Some(path) => Some(path.clone()),
+            None => self.project_dir.as_deref().and_then(Config::project_config_path),
+        };
+        if let Some(path) = project_file {
+            merge(&mut value, read_file(&path)?);
+            files.push(path);
+        }
+        
+        let env = match &self.env {
+            Some(vars) => vars.clone(),
+            None => std::env::vars().collect(),
+        };
+        for (name, raw) in env {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else { continue };
+            let key = key.to_lowercase().replace("__", ".");
+            if lookup(&value, &key).is_none() {
+                tracing::debug!("Ignoring unknown config variable {}", name);
+                continue;
+            }
+            set_key(&mut value, &key, &raw).with_context(|| format!("Invalid value in {}", name))?;
+        }
+        
+        for (key, raw) in &self.overrides {
+            set_key(&mut value, key, raw)?;
+        }
+        
+        let config = serde_json::from_value(value).context("Invalid configuration")?;
+        Ok(LoadedConfig { config, files })
+    }
+}
+
+/// Set `key` to `raw` in the configuration file at `path`, creating it if needed
+///
+/// The value is typed after the built-in default for that key and the result
+/// is validated before the file is written.
+pub fn set_in_file(path: &Path, key: &str, raw: &str) -> Result<()> {
+    let mut file_value = if path.is_file() {
+        read_file(path)?
+    } else {
+        Value::Object(Default::default())
+    };
+    
+    let mut typed = serde_json::to_value(Config::default())?;
+    set_key(&mut typed, key, raw)?;
+    let new_value = lookup(&typed, key).cloned().unwrap_or(Value::Null);
+    insert(&mut file_value, key, new_value);
+    
+    // Make sure the file still produces a valid configuration
+    let mut check = serde_json::to_value(Config::default())?;
+    merge(&mut check, file_value.clone());
+    serde_json::from_value::<Config>(check).context("Invalid configuration")?;
+    
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents = if is_json(path) {
+        serde_json::to_string_pretty(&file_value)?
+    } else {
+        toml::to_string_pretty(&file_value)?
+    };
+    std::fs::write(path, contents)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().map(|e| e == "json").unwrap_or(false)
+}
+
+/// Read a TOML or JSON configuration file into a generic value
+fn read_file(path: &Path) -> Result<Value> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let value = if is_json(path) {
+        serde_json::from_str(&text)?
+    } else {
+        toml::from_str::<Value>(&text)?
+    };
+    if !value.is_object() {
+        bail!("Config file {} must contain a table", path.display());
+    }
+    Ok(value)
+}
+
+/// Deep-merge `layer` into `base`
+fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (k, v) in layer {
+                match base.get_mut(&k) {
+                    Some(existing) => merge(existing, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+fn insert(value: &mut Value, key: &str, new_value: Value) {
+    let mut current = value;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        if !current.is_object() {
+            *current = Value::Object(Default::default());
+        }
+        let map = current.as_object_mut().expect("object");
+        if parts.peek().is_none() {
+            map.insert(part.to_string(), new_value);
+            return;
+        }
+        current = map.entry(part.to_string()).or_insert_with(|| Value::Object(Default::default()));
+    }
+}
+
+/// Parse `raw` using the type of the existing value at `key` and store it
+fn set_key(value: &mut Value, key: &str, raw: &str) -> Result<()> {
+    let existing = lookup(value, key).ok_or_else(|| anyhow!("Unknown config key: {}", key))?;
+    let parsed = parse_like(existing, raw)
+        .ok_or_else(|| anyhow!("Cannot use '{}' for config key {}", raw, key))?;
+    insert(value, key, parsed);
+    Ok(())
+}
+
+fn parse_like(template: &Value, raw: &str) -> Option<Value> {
+    let raw = raw.trim();
+    match template {
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        Value::Number(_) => raw.parse::<u64>().ok().map(Value::from)
+            .or_else(|| raw.parse::<f64>().ok().map(Value::from)),
+        Value::Array(items) => {
+            if raw.starts_with('[') {
+                return serde_json::from_str(raw).ok();
+            }
+            let element = items.first().cloned().unwrap_or(Value::String(String::new()));
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_like(&element, s))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+        Value::String(_) => Some(Value::String(raw.to_string())),
+        Value::Null | Value::Object(_) => {
+            Some(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())))
         }
     }
 }
@@ -150,3 +562,34 @@ pub mod sources {
         pub const GITHUB_RAW: &str = "https://raw.githubusercontent.com/google/fonts/main/ofl";
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_layers_override_defaults() {
+        let path = std::env::temp_dir().join(format!("dx-font-config-test-{}.toml", std::process::id()));
+        std::fs::write(&path, "timeout_seconds = 5\npreferred_formats = [\"ttf\"]\n").unwrap();
+        
+        let loaded = ConfigLoader::empty()
+            .file(&path)
+            .env([("DX_FONT_MAX_CONCURRENT_DOWNLOADS", "2"), ("DX_FONT_TIMEOUT_SECONDS", "7")])
+            .set("preferred_formats", "woff2, woff")
+            .load()
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+        
+        assert_eq!(loaded.files, vec![path]);
+        assert_eq!(loaded.config.timeout_seconds, 7);
+        assert_eq!(loaded.config.max_concurrent_downloads, 2);
+        assert_eq!(loaded.config.preferred_formats, vec!["woff2", "woff"]);
+        assert_eq!(loaded.config.cache_ttl_seconds, Config::default().cache_ttl_seconds);
+    }
+    
+    #[test]
+    fn test_invalid_override_is_rejected() {
+        assert!(ConfigLoader::empty().set("timeout_seconds", "soon").load().is_err());
+        assert!(ConfigLoader::empty().set("no_such_key", "1").load().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! `@font-face` stylesheets for self-hosted fonts
+//!
+//! [`crate::cdn`] links to third-party CDNs; this module instead describes the
+//! files a download wrote to disk. Each weight, style and subset gets one
+//! `@font-face` rule whose `src` lists the available formats from smallest
+//! to largest (woff2, woff, ttf, otf), addressed under the configured public
+//! URL prefix.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::path::{Component, Path};
+
+use crate::archive;
+use crate::batch::DownloadRequest;
+use crate::config::CssConfig;
+use crate::download::DownloadResult;
+use crate::models::{FontCategory, FontStyle};
+
+/// Formats in `src` order with their `format()` hint
+const FORMATS: &[(&str, &str)] = &[
+    ("woff2", "woff2"),
+    ("woff", "woff"),
+    ("ttf", "truetype"),
+    ("otf", "opentype"),
+];
+
+/// `unicode-range` of the Google Fonts subsets, as served by the Google Fonts CSS API
+const SUBSET_RANGES: &[(&str, &str)] = &[
+    ("latin-ext", "U+0100-02BA, U+02BD-02C5, U+02C7-02CC, U+02CE-02D7, U+02DD-02FF, U+0304, U+0308, U+0329, U+1D00-1DBF, U+1E00-1E9F, U+1EF2-1EFF, U+2020, U+20A0-20AB, U+20AD-20C0, U+2113, U+2C60-2C7F, U+A720-A7FF"),
+    ("latin", "U+0000-00FF, U+0131, U+0152-0153, U+02BB-02BC, U+02C6, U+02DA, U+02DC, U+0304, U+0308, U+0329, U+2000-206F, U+20AC, U+2122, U+2191, U+2193, U+2212, U+2215, U+FEFF, U+FFFD"),
+    ("cyrillic-ext", "U+0460-052F, U+1C80-1C8A, U+20B4, U+2DE0-2DFF, U+A640-A69F, U+FE2E-FE2F"),
+    ("cyrillic", "U+0301, U+0400-045F, U+0490-0491, U+04B0-04B1, U+2116"),
+    ("greek-ext", "U+1F00-1FFF"),
+    ("greek", "U+0370-0377, U+037A-037F, U+0384-038A, U+038C, U+038E-03A1, U+03A3-03FF"),
+    ("vietnamese", "U+0102-0103, U+0110-0111, U+0128-0129, U+0168-0169, U+01A0-01A1, U+01AF-01B0, U+0300-0301, U+0303-0304, U+0308-0309, U+0323, U+0329, U+1EA0-1EF9, U+20AB"),
+    ("hebrew", "U+0307-0308, U+0590-05FF, U+200C-2010, U+20AA, U+25CC, U+FB1D-FB4F"),
+    ("arabic", "U+0600-06FF, U+0750-077F, U+0870-088E, U+0890-0891, U+0897-08E1, U+08E3-08FF, U+200C-200E, U+2010-2011, U+204F, U+2E41, U+FB50-FDFF, U+FE70-FE74, U+FE76-FEFC"),
+];
+
+/// One `@font-face` rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontFace {
+    pub family: String,
+    /// `400`, or `100 900` for variable fonts
+    pub weight: String,
+    pub style: FontStyle,
+    pub display: String,
+    /// `None` when the file is not limited to known subsets
+    pub unicode_range: Option<String>,
+    /// `(url, format)` pairs in preference order
+    pub sources: Vec<(String, &'static str)>,
+}
+
+impl fmt::Display for FontFace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sources: Vec<String> = self.sources.iter()
+            .map(|(url, format)| format!("url(\"{}\") format(\"{}\")", url, format))
+            .collect();
+        writeln!(f, "@font-face {{")?;
+        writeln!(f, "  font-family: \"{}\";", self.family)?;
+        writeln!(f, "  font-style: {};", style_name(self.style))?;
+        writeln!(f, "  font-weight: {};", self.weight)?;
+        writeln!(f, "  font-display: {};", self.display)?;
+        writeln!(f, "  src: {};", sources.join(",\n       "))?;
+        if let Some(range) = &self.unicode_range {
+            writeln!(f, "  unicode-range: {};", range)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// Stylesheet for every successful download; `requests` and `results` line up
+pub fn stylesheet(requests: &[DownloadRequest], results: &[DownloadResult], config: &CssConfig) -> String {
+    let mut css = String::from("/* Generated by dx-font */\n");
+    let mut properties = Vec::new();
+
+    for (request, result) in requests.iter().zip(results).filter(|(_, r)| r.success) {
+        let family = result.family.clone().unwrap_or_else(|| result.font_id.clone());
+        let paths: Vec<&Path> = result.files.iter().map(|f| f.path.as_path()).collect();
+        for face in font_faces(&family, &paths, &request.options.output_dir, &request.options.subsets, config) {
+            let _ = write!(css, "\n{}", face);
+        }
+        properties.push(format!(
+            "  --font-{}: \"{}\", {};",
+            property_name(&result.font_id),
+            family,
+            fallback_stack(result.category.as_ref()),
+        ));
+    }
+
+    if config.custom_properties && !properties.is_empty() {
+        let _ = write!(css, "\n:root {{\n{}\n}}\n", properties.join("\n"));
+    }
+    css
+}
+
+/// Rules for the font files among `files`, which live under `root`
+///
+/// Files whose name names a subset (`inter-cyrillic-400-normal.woff2`) cover
+/// that subset; other files are assumed to cover the `subsets` requested.
+pub fn font_faces(family: &str, files: &[&Path], root: &Path, subsets: &[String], config: &CssConfig) -> Vec<FontFace> {
+    // (weight key, style, subsets) -> format -> url
+    let mut groups: BTreeMap<(u16, u8, Vec<String>), BTreeMap<usize, String>> = BTreeMap::new();
+
+    for path in files {
+        let Some(format) = archive::font_format(path) else {
+            continue;
+        };
+        let Some(rank) = FORMATS.iter().position(|(ext, _)| *ext == format) else {
+            continue;
+        };
+        let info = archive::parse_font_file(path);
+        let weight = info.weight.map_or(0, |w| w.to_numeric());
+        let style = match info.style {
+            FontStyle::Normal => 0,
+            FontStyle::Italic => 1,
+        };
+        let mut covered = file_subsets(path);
+        if covered.is_empty() {
+            covered = subsets.to_vec();
+        }
+        groups.entry((weight, style, covered))
+            .or_default()
+            .entry(rank)
+            .or_insert_with(|| public_url(&config.public_url, path.strip_prefix(root).unwrap_or(path)));
+    }
+
+    groups.into_iter()
+        .map(|((weight, style, covered), urls)| FontFace {
+            family: family.to_string(),
+            weight: if weight == 0 { "100 900".to_string() } else { weight.to_string() },
+            style: if style == 0 { FontStyle::Normal } else { FontStyle::Italic },
+            display: config.font_display.clone(),
+            unicode_range: unicode_range(&covered),
+            sources: urls.into_iter().map(|(rank, url)| (url, FORMATS[rank].1)).collect(),
+        })
+        .collect()
+}
+
+/// Combined `unicode-range` of `subsets`; `None` if any is unknown
+pub fn unicode_range(subsets: &[String]) -> Option<String> {
+    if subsets.is_empty() {
+        return None;
+    }
+    let ranges: Option<Vec<&str>> = subsets.iter()
+        .map(|subset| SUBSET_RANGES.iter().find(|(name, _)| name == subset).map(|(_, range)| *range))
+        .collect();
+    ranges.map(|r| r.join(", "))
+}
+
+/// Generic fallbacks for a family of `category`
+pub fn fallback_stack(category: Option<&FontCategory>) -> &'static str {
+    match category {
+        Some(FontCategory::Serif) => "ui-serif, Georgia, Cambria, \"Times New Roman\", serif",
+        Some(FontCategory::Monospace) => "ui-monospace, SFMono-Regular, Menlo, Consolas, \"Liberation Mono\", monospace",
+        Some(FontCategory::Handwriting) => "\"Segoe Script\", \"Bradley Hand\", cursive",
+        Some(FontCategory::SansSerif) | Some(FontCategory::Display) | None => {
+            "system-ui, -apple-system, \"Segoe UI\", Roboto, \"Helvetica Neue\", Arial, sans-serif"
+        }
+    }
+}
+
+/// Subsets named in a file name such as `roboto-v30-cyrillic_latin-700.woff2`
+fn file_subsets(path: &Path) -> Vec<String> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let mut rest = format!("-{}-", stem.to_lowercase().replace('_', "-"));
+    let mut found = Vec::new();
+    // Longer names come first in the table, so `latin-ext` is not read as `latin`
+    for (name, _) in SUBSET_RANGES {
+        let token = format!("-{}-", name);
+        if rest.contains(&token) {
+            rest = rest.replacen(&token, "-", 1);
+            found.push(name.to_string());
+        }
+    }
+    found.sort();
+    found
+}
+
+/// `prefix/relative/path` with `/` separators and spaces escaped
+fn public_url(prefix: &str, relative: &Path) -> String {
+    let parts: Vec<String> = relative.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().replace(' ', "%20")),
+            _ => None,
+        })
+        .collect();
+    format!("{}/{}", prefix.trim_end_matches('/'), parts.join("/"))
+}
+
+/// `Open Sans` and `open-sans` both become `open-sans`
+fn property_name(font_id: &str) -> String {
+    font_id.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn style_name(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Normal => "normal",
+        FontStyle::Italic => "italic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_faces_group_and_order_sources() {
+        let files = [
+            Path::new("fonts/Inter/Inter-Bold.ttf"),
+            Path::new("fonts/Inter/Inter-Bold.woff2"),
+            Path::new("fonts/Inter/Inter-Italic.woff"),
+            Path::new("fonts/Inter/OFL.txt"),
+            Path::new("fonts/Inter/inter-cyrillic-400-normal.woff2"),
+        ];
+        let config = CssConfig::default();
+        let faces = font_faces("Inter", &files, Path::new("fonts"), &["latin".to_string()], &config);
+
+        assert_eq!(faces.len(), 3);
+        assert_eq!(faces[0].weight, "400");
+        assert_eq!(faces[0].sources, [("/fonts/Inter/inter-cyrillic-400-normal.woff2".to_string(), "woff2")]);
+        assert_eq!(faces[0].unicode_range.as_deref(), Some(SUBSET_RANGES[3].1));
+        assert_eq!(faces[1].style, FontStyle::Italic);
+        assert_eq!(faces[2].sources, [
+            ("/fonts/Inter/Inter-Bold.woff2".to_string(), "woff2"),
+            ("/fonts/Inter/Inter-Bold.ttf".to_string(), "truetype"),
+        ]);
+
+        let css = faces[2].to_string();
+        assert!(css.contains("font-weight: 700;"), "{}", css);
+        assert!(css.contains("font-display: swap;"), "{}", css);
+        assert_eq!(unicode_range(&["latin".to_string(), "klingon".to_string()]), None);
+        assert_eq!(property_name("Open Sans"), "open-sans");
+    }
+}
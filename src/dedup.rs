@@ -0,0 +1,115 @@
+//! Cross-provider deduplication
+//!
+//! Popular families ship from several providers at once. This module folds
+//! those rows into one [`CanonicalFamily`] per normalized family name and
+//! license, and picks the preferred source by a provider priority order.
+
+use std::collections::HashMap;
+use crate::models::{CanonicalFamily, Font, FontLicense, FontProvider, FontSource};
+
+/// Default order used to pick the preferred source of a family
+pub fn default_priority() -> Vec<FontProvider> {
+    vec![
+        FontProvider::GoogleFontsRepo,
+        FontProvider::GoogleFonts,
+        FontProvider::Fontsource,
+        FontProvider::BunnyFonts,
+        FontProvider::FontShare,
+        FontProvider::GitHub,
+        FontProvider::FontLibrary,
+        FontProvider::FontSquirrel,
+        FontProvider::FontSpace,
+        FontProvider::Fonts1001,
+        FontProvider::DaFont,
+    ]
+}
+
+/// Group `fonts` into canonical families
+///
+/// Families keep the order in which they first appear in `fonts`, so ranked
+/// input stays ranked. Providers missing from `priority` sort after the listed
+/// ones.
+pub fn merge(fonts: &[Font], priority: &[FontProvider]) -> Vec<CanonicalFamily> {
+    let mut index: HashMap<(String, Option<FontLicense>), usize> = HashMap::new();
+    let mut groups: Vec<Vec<&Font>> = Vec::new();
+
+    for font in fonts {
+        let key = (family_key(&font.name), font.license.clone());
+        match index.get(&key) {
+            Some(&i) => groups[i].push(font),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(vec![font]);
+            }
+        }
+    }
+
+    groups.into_iter()
+        .map(|mut group| {
+            // Stable sort keeps the incoming order among equally ranked providers
+            group.sort_by_key(|f| rank_of(&f.provider, priority));
+            let preferred = group[0];
+            // Every row shares the license, so the best-backed one speaks for the family
+            let evidence = group.iter()
+                .max_by_key(|f| f.license_confidence)
+                .expect("groups are never empty");
+
+            CanonicalFamily {
+                name: preferred.name.clone(),
+                category: group.iter().find_map(|f| f.category.clone()),
+                license: preferred.license.clone(),
+                license_source: evidence.license_source,
+                license_confidence: evidence.license_confidence,
+                preferred: preferred.provider.clone(),
+                score: group.iter().filter_map(|f| f.score).reduce(f32::max),
+                sources: group.iter()
+                    .map(|f| FontSource {
+                        provider: f.provider.clone(),
+                        id: f.id.clone(),
+                        variant_count: f.variant_count,
+                        preview_url: f.preview_url.clone(),
+                        download_url: f.download_url.clone(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Name used to decide whether two rows are the same family
+fn family_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn rank_of(provider: &FontProvider, priority: &[FontProvider]) -> usize {
+    priority.iter().position(|p| p == provider).unwrap_or(priority.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(name: &str, provider: FontProvider, license: FontLicense) -> Font {
+        Font { license: Some(license), ..Font::test(name, provider) }
+    }
+
+    #[test]
+    fn test_merge_by_name_and_license() {
+        let fonts = vec![
+            font("Inter", FontProvider::FontSpace, FontLicense::OFL),
+            font("inter", FontProvider::GoogleFonts, FontLicense::OFL),
+            font("Lato", FontProvider::DaFont, FontLicense::OFL),
+            font("Inter", FontProvider::FontShare, FontLicense::FreeCommercial),
+        ];
+        let families = merge(&fonts, &default_priority());
+
+        assert_eq!(families.len(), 3);
+        assert_eq!(families[0].preferred, FontProvider::GoogleFonts);
+        assert_eq!(families[0].provider_names(), ["Google Fonts", "FontSpace"]);
+        assert_eq!(families[1].name, "Lato");
+        assert_eq!(families[2].sources.len(), 1);
+    }
+}
@@ -2,56 +2,184 @@
 //!
 //! Handles downloading fonts from various providers with progress indication.
 
-use anyhow::{Result, Context};
 use futures::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
-use reqwest::Client;
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::fs::{self, File};
+use tokio::fs::{self, File, OpenOptions};
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+use url::Url;
 
-use crate::models::{FontProvider, DownloadOptions};
-use crate::providers::{create_http_client, ProviderRegistry};
+use crate::archive::{self, FontFileInfo};
+use crate::batch::DownloadRequest;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::inspect;
+use crate::models::{DownloadOptions, FontCategory, FontLicense, FontProvider, FontStyle, FontVariant, FontWeight, LicenseConfidence, LicenseSource};
+use crate::providers::{create_http_client_with, ProviderRegistry};
 
 /// Font downloader with progress indication
 pub struct FontDownloader {
-    client: Client,
+    client: HttpClient,
     registry: Arc<ProviderRegistry>,
     multi_progress: MultiProgress,
+    /// Set from `Config::offline`; every download then fails up front
+    offline: bool,
+    /// Limits concurrent transfers to `Config::max_concurrent_downloads`
+    permits: Arc<Semaphore>,
+    /// Fonts processed at once by [`FontDownloader::download_many`]
+    max_concurrent: usize,
 }
 
 impl FontDownloader {
     /// Create a new font downloader
     pub fn new() -> Result<Self> {
-        let client = create_http_client()?;
-        let registry = ProviderRegistry::with_defaults()?;
+        Self::with_config(&Config::default())
+    }
+    
+    /// Create a font downloader whose HTTP client and concurrency follow `config`
+    pub fn with_config(config: &Config) -> Result<Self> {
+        let client = create_http_client_with(config)?;
+        let registry = ProviderRegistry::with_config(config)?;
         
         Ok(Self {
             client,
             registry: Arc::new(registry),
             multi_progress: MultiProgress::new(),
+            offline: config.offline,
+            permits: Arc::new(Semaphore::new(config.max_concurrent_downloads.max(1))),
+            max_concurrent: config.max_concurrent_downloads.max(1),
         })
     }
     
+    /// Download many fonts, up to `Config::max_concurrent_downloads` at a time
+    ///
+    /// Never fails as a whole: each font gets a [`DownloadResult`], in the
+    /// order of `requests`, that records its own success or error.
+    pub async fn download_many(&self, requests: &[DownloadRequest]) -> Vec<DownloadResult> {
+        let overall = self.multi_progress.add(ProgressBar::new(requests.len() as u64));
+        overall.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.green/white}] {pos}/{len} fonts {msg}")
+                .unwrap()
+                .progress_chars("#>-")
+        );
+        
+        let results = futures::stream::iter(requests)
+            .map(|request| {
+                let overall = &overall;
+                async move {
+                    let result = self.download_font(&request.provider, &request.font_id, &request.options)
+                        .await
+                        .unwrap_or_else(|e| DownloadResult::failed(request, &e));
+                    overall.inc(1);
+                    result
+                }
+            })
+            .buffered(self.max_concurrent)
+            .collect::<Vec<_>>()
+            .await;
+        
+        let failed = results.iter().filter(|r| !r.success).count();
+        overall.finish_with_message(format!("({} failed)", failed));
+        results
+    }
+    
+    /// Downloads always need the network, the catalog snapshot can't serve them
+    fn ensure_online(&self, what: &str) -> Result<()> {
+        match self.offline {
+            true => Err(Error::Offline(format!("Cannot download {} in offline mode", what))),
+            false => Ok(()),
+        }
+    }
+    
     /// Download a font by ID from a specific provider
+    ///
+    /// Resolves the family and fetches every variant that matches the
+    /// requested weights, styles and formats concurrently. Providers without
+    /// per-variant files fall back to the family package, which is filtered
+    /// while it is unpacked. Requested combinations the provider does not offer
+    /// are listed in [`DownloadResult::unavailable`].
     pub async fn download_font(
         &self,
         provider: &FontProvider,
         font_id: &str,
         options: &DownloadOptions,
-    ) -> Result<Vec<PathBuf>> {
-        // Ensure output directory exists
-        fs::create_dir_all(&options.output_dir).await?;
-        
-        // Get download URL from provider
-        let download_url = self.get_download_url(provider, font_id).await?;
-        
-        // Download the font
-        self.download_file(&download_url, &options.output_dir, font_id).await
+    ) -> Result<DownloadResult> {
+        self.ensure_online(font_id)?;
+        let source = self.registry.get(provider)
+            .ok_or_else(|| Error::ProviderNotFound { provider: provider.name().to_string() })?;
+        let family = source.get_font_family(font_id).await
+            .map_err(|e| e.with_provider(source.name()))?;
+        
+        let prefix = archive::family_dir_name(&family.name);
+        let dir = options.output_dir.join(&prefix);
+        fs::create_dir_all(&dir).await?;
+        
+        let offered: Vec<VariantSpec> = family.variants.iter()
+            .filter(|v| v.file_url.is_some())
+            .flat_map(VariantSpec::offered_by)
+            .collect();
+        
+        let (files, offered) = if offered.is_empty() {
+            // Without per-variant files the package is all or nothing, so any
+            // matching font already on disk counts as a finished download
+            let url = match &family.download_url {
+                Some(url) => url.clone(),
+                None => source.get_download_url(font_id).await?,
+            };
+            let existing = if options.force { Vec::new() } else { existing_fonts(&dir, options).await? };
+            let files = if existing.is_empty() {
+                let name = format!("{}-{}", prefix, archive::parse_font_file(Path::new(&url)).suffix());
+                let path = self.download_file(&url, &dir, &name).await?;
+                describe(self.unpack(path, &family.name, options).await?, &url, false).await?
+            } else {
+                describe(existing, &url, true).await?
+            };
+            // What the package turned out to contain
+            let offered = files.iter().filter_map(|f| VariantSpec::from_file(&f.path)).collect();
+            (files, offered)
+        } else {
+            let jobs = variant_jobs(&family.variants, &prefix, options);
+            let downloads = jobs.iter().map(|job| self.download_variant(job, &dir, &family.name, options));
+            let files = futures::future::try_join_all(downloads).await?.into_iter().flatten().collect();
+            (files, offered)
+        };
+        
+        // A license the font itself names outranks anything short of the provider's metadata
+        let (license, license_source, license_confidence) = match name_table_license(&files).await {
+            Some(license) if family.license_confidence < LicenseConfidence::Verified => {
+                (Some(license), LicenseSource::NameTable, LicenseConfidence::Verified)
+            }
+            _ => (family.license, family.license_source, family.license_confidence),
+        };
+        
+        Ok(DownloadResult {
+            font_id: font_id.to_string(),
+            provider: provider.clone(),
+            files,
+            success: true,
+            error: None,
+            unavailable: unavailable(options, &offered),
+            last_modified: family.last_modified,
+            family: Some(family.name),
+            category: family.category,
+            license,
+            license_source,
+            license_confidence,
+        })
     }
     
     /// Download a font using a direct URL
+    ///
+    /// The file is saved as-is; archives are not unpacked.
     pub async fn download_from_url(
         &self,
         url: &str,
@@ -60,18 +188,19 @@ impl FontDownloader {
     ) -> Result<PathBuf> {
         fs::create_dir_all(output_dir).await?;
         
-        let paths = self.download_file(url, output_dir, filename).await?;
-        paths.into_iter().next().ok_or_else(|| anyhow::anyhow!("No files downloaded"))
+        self.download_file(url, output_dir, filename).await
     }
     
     /// Download using Google Webfonts Helper (provides zip with all formats)
+    ///
+    /// Returns the extracted font files, laid out as described in [`archive`].
     pub async fn download_google_font(
         &self,
         font_id: &str,
         output_dir: &Path,
         formats: &[&str],
         subsets: &[&str],
-    ) -> Result<PathBuf> {
+    ) -> Result<Vec<PathBuf>> {
         fs::create_dir_all(output_dir).await?;
         
         let formats_str = formats.join(",");
@@ -82,40 +211,14 @@ impl FontDownloader {
             font_id, subsets_str, formats_str
         );
         
-        let output_path = output_dir.join(format!("{}.zip", font_id));
+        let path = self.download_file(&url, output_dir, font_id).await?;
         
-        let pb = self.create_progress_bar(font_id);
-        
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send request")?;
-        
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to download font: HTTP {}",
-                response.status()
-            ));
-        }
-        
-        let total_size = response.content_length().unwrap_or(0);
-        pb.set_length(total_size);
-        
-        let mut file = File::create(&output_path).await?;
-        let mut stream = response.bytes_stream();
-        let mut downloaded = 0u64;
-        
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("Error reading chunk")?;
-            file.write_all(&chunk).await?;
-            downloaded += chunk.len() as u64;
-            pb.set_position(downloaded);
-        }
-        
-        pb.finish_with_message(format!("Downloaded {}", font_id));
-        
-        Ok(output_path)
+        let options = DownloadOptions {
+            output_dir: output_dir.to_path_buf(),
+            formats: formats.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        };
+        self.unpack(path, font_id, &options).await
     }
     
     /// Download font from Fontsource via CDN
@@ -133,98 +236,193 @@ impl FontDownloader {
             font_id, font_id, weight, style
         );
         
-        let filename = format!("{}-{}-{}.woff2", font_id, weight, style);
-        let output_path = output_dir.join(&filename);
-        
-        let pb = self.create_progress_bar(&filename);
-        
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send request")?;
-        
-        if !response.status().is_success() {
-            pb.finish_with_message(format!("Failed: {}", filename));
-            return Err(anyhow::anyhow!(
-                "Failed to download font: HTTP {}",
-                response.status()
-            ));
+        self.download_file(&url, output_dir, &format!("{}-{}-{}", font_id, weight, style)).await
+    }
+    
+    /// Fetch one variant file (or package) and unpack it into `dir`
+    ///
+    /// A variant whose final file already exists is skipped unless
+    /// `options.force` is set. Files only ever appear at their final path
+    /// through an atomic rename, so an existing file is a complete one.
+    async fn download_variant(
+        &self,
+        job: &VariantJob,
+        dir: &Path,
+        family: &str,
+        options: &DownloadOptions,
+    ) -> Result<Vec<DownloadedFile>> {
+        let url = with_subsets(&job.url, &options.subsets);
+        let target = dir.join(format!("{}.{}", job.base, job.format));
+        if job.single && !options.force && fs::try_exists(&target).await? {
+            return describe(vec![target], &url, true).await;
         }
         
-        let total_size = response.content_length().unwrap_or(0);
-        pb.set_length(total_size);
-        
-        let mut file = File::create(&output_path).await?;
-        let mut stream = response.bytes_stream();
-        let mut downloaded = 0u64;
-        
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("Error reading chunk")?;
-            file.write_all(&chunk).await?;
-            downloaded += chunk.len() as u64;
-            pb.set_position(downloaded);
+        let path = self.download_file(&url, dir, &job.name).await?;
+        if archive::is_archive(&path) || job.name == job.base {
+            return describe(self.unpack(path, family, options).await?, &url, false).await;
         }
         
-        pb.finish_with_message(format!("Downloaded {}", filename));
-        
-        Ok(output_path)
+        // Disambiguated names are only needed while archives are in flight
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        let target = dir.join(format!("{}.{}", job.base, ext));
+        fs::rename(&path, &target).await?;
+        describe(vec![target], &url, false).await
     }
     
-    async fn get_download_url(&self, provider: &FontProvider, font_id: &str) -> Result<String> {
-        for p in self.registry.providers() {
-            if p.name() == provider.name() {
-                return p.get_download_url(font_id).await;
-            }
+    /// Extract `path` into the family layout if it is an archive
+    ///
+    /// The archive is removed once its fonts are out. It is kept, and an error
+    /// returned, when nothing in it matches `options`.
+    async fn unpack(&self, path: PathBuf, family: &str, options: &DownloadOptions) -> Result<Vec<PathBuf>> {
+        if !archive::is_archive(&path) {
+            return Ok(vec![path]);
         }
         
-        Err(anyhow::anyhow!("Provider not found: {:?}", provider))
+        let (archive_path, family, options) = (path.clone(), family.to_string(), options.clone());
+        let files = tokio::task::spawn_blocking(move || archive::extract(&archive_path, &family, &options))
+            .await
+            .map_err(anyhow::Error::from)??;
+        
+        if !files.iter().any(|f| archive::font_format(f).is_some()) {
+            return Err(Error::Archive {
+                path: path.display().to_string(),
+                message: "no font files match the requested formats, weights and styles".to_string(),
+            });
+        }
+        fs::remove_file(&path).await?;
+        Ok(files)
     }
     
+    /// Stream `url` into `output_dir/<name>.<ext>`
+    ///
+    /// Bytes go to `<name>.part` first, which is renamed into place once the
+    /// length matches `Content-Length`. A `.part` left by an interrupted
+    /// transfer is resumed with a `Range` request guarded by `If-Range`, so a
+    /// file that changed on the server is fetched again from the start.
     async fn download_file(
         &self,
         url: &str,
         output_dir: &Path,
         name: &str,
-    ) -> Result<Vec<PathBuf>> {
+    ) -> Result<PathBuf> {
+        self.ensure_online(url)?;
+        let _permit = self.permits.acquire().await.map_err(anyhow::Error::from)?;
         let pb = self.create_progress_bar(name);
         
-        let response = self.client
-            .get(url)
-            .send()
-            .await
-            .context("Failed to send request")?;
+        match self.transfer(url, output_dir, name, &pb).await {
+            Ok(path) => {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                pb.finish_with_message(format!("Downloaded {}", file_name));
+                Ok(path)
+            }
+            Err(e) => {
+                pb.abandon_with_message(format!("Failed: {}", name));
+                Err(e)
+            }
+        }
+    }
+    
+    async fn transfer(
+        &self,
+        url: &str,
+        output_dir: &Path,
+        name: &str,
+        pb: &ProgressBar,
+    ) -> Result<PathBuf> {
+        let part = output_dir.join(format!("{}.part", name));
+        // ETag or Last-Modified of the response the partial file came from
+        let validator_path = output_dir.join(format!("{}.part.validator", name));
+        let validator = fs::read_to_string(&validator_path).await.ok();
+        let mut offset = match (fs::metadata(&part).await, &validator) {
+            (Ok(meta), Some(_)) => meta.len(),
+            _ => 0,
+        };
+        
+        let response = loop {
+            // Fonts and archives are already compressed; identity keeps byte
+            // ranges meaningful
+            let mut request = self.client.get(url).header(ACCEPT_ENCODING, "identity");
+            if let (true, Some(validator)) = (offset > 0, &validator) {
+                request = request
+                    .header(RANGE, &format!("bytes={}-", offset))
+                    .header(IF_RANGE, validator.as_str());
+            }
+            let response = request.send().await?;
+            
+            if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+                // The partial file no longer matches the remote one
+                fs::remove_file(&part).await?;
+                offset = 0;
+                continue;
+            }
+            break response;
+        };
         
         if !response.status().is_success() {
-            pb.finish_with_message(format!("Failed: {}", name));
-            return Err(anyhow::anyhow!(
-                "Failed to download: HTTP {}",
-                response.status()
-            ));
+            return Err(Error::HttpStatus {
+                provider: None,
+                status: response.status().as_u16(),
+                url: url.to_string(),
+            });
+        }
+        
+        // A 200 means the range was refused or the file changed: start over
+        let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            offset = 0;
+            match response_validator(&response) {
+                Some(validator) => fs::write(&validator_path, validator).await?,
+                None => {
+                    let _ = fs::remove_file(&validator_path).await;
+                }
+            }
         }
+        let expected = if resumed {
+            content_range_total(&response).or_else(|| response.content_length().map(|len| offset + len))
+        } else {
+            response.content_length()
+        };
         
         // Determine file extension from content-type or URL
         let extension = self.get_extension_from_response(&response, url);
-        let filename = format!("{}.{}", name, extension);
-        let output_path = output_dir.join(&filename);
+        let output_path = output_dir.join(format!("{}.{}", name, extension));
         
-        let total_size = response.content_length().unwrap_or(0);
-        pb.set_length(total_size);
+        pb.set_length(expected.unwrap_or(0));
+        pb.set_position(offset);
         
-        let mut file = File::create(&output_path).await?;
+        let mut file = if resumed {
+            OpenOptions::new().append(true).open(&part).await?
+        } else {
+            File::create(&part).await?
+        };
         let mut stream = response.bytes_stream();
-        let mut downloaded = 0u64;
+        let mut downloaded = offset;
         
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("Error reading chunk")?;
+            let chunk = chunk?;
             file.write_all(&chunk).await?;
             downloaded += chunk.len() as u64;
             pb.set_position(downloaded);
         }
+        file.flush().await?;
+        file.sync_all().await?;
+        drop(file);
+        
+        if let Some(expected) = expected {
+            if downloaded != expected {
+                // Keep the partial file so the next attempt can resume
+                return Err(Error::Incomplete {
+                    url: url.to_string(),
+                    expected,
+                    received: downloaded,
+                });
+            }
+        }
         
-        pb.finish_with_message(format!("Downloaded {}", filename));
+        fs::rename(&part, &output_path).await?;
+        let _ = fs::remove_file(&validator_path).await;
         
-        Ok(vec![output_path])
+        Ok(output_path)
     }
     
     fn create_progress_bar(&self, name: &str) -> ProgressBar {
@@ -275,11 +473,440 @@ impl FontDownloader {
 }
 
 /// Download result
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DownloadResult {
     pub font_id: String,
     pub provider: FontProvider,
-    pub files: Vec<PathBuf>,
+    pub files: Vec<DownloadedFile>,
     pub success: bool,
     pub error: Option<String>,
+    /// Requested weight/style/format combinations the provider does not offer
+    pub unavailable: Vec<VariantSpec>,
+    /// Provider's last modification date of the family, when it reports one
+    pub last_modified: Option<String>,
+    /// Display name of the family (`Open Sans`)
+    pub family: Option<String>,
+    pub category: Option<FontCategory>,
+    pub license: Option<FontLicense>,
+    pub license_source: LicenseSource,
+    pub license_confidence: LicenseConfidence,
+}
+
+impl DownloadResult {
+    /// Result for a font that could not be downloaded
+    pub fn failed(request: &DownloadRequest, error: &Error) -> Self {
+        Self {
+            font_id: request.font_id.clone(),
+            provider: request.provider.clone(),
+            files: Vec::new(),
+            success: false,
+            error: Some(error.to_string()),
+            unavailable: Vec::new(),
+            last_modified: None,
+            family: None,
+            category: None,
+            license: None,
+            license_source: LicenseSource::default(),
+            license_confidence: LicenseConfidence::default(),
+        }
+    }
+    
+    pub fn commercial_use_confirmed(&self) -> bool {
+        crate::models::commercial_use_confirmed(self.license.as_ref(), self.license_confidence)
+    }
+}
+
+/// License named in the name table of the first downloaded font that has one
+async fn name_table_license(files: &[DownloadedFile]) -> Option<FontLicense> {
+    for file in files.iter().filter(|f| archive::font_format(&f.path).is_some()) {
+        let Ok(data) = fs::read(&file.path).await else {
+            continue;
+        };
+        if let Some(license) = inspect::inspect_bytes(&data).ok().and_then(|info| info.license_kind()) {
+            return Some(license);
+        }
+    }
+    None
+}
+
+/// A file produced by a download
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadedFile {
+    pub path: PathBuf,
+    /// Where the file (or the archive it came from) was fetched
+    pub url: String,
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+    /// The file was already present and was not downloaded again
+    pub skipped: bool,
+}
+
+/// A weight/style/format combination; `None` stands for "any"
+///
+/// A `None` weight on an offered variant marks a variable font, which covers
+/// every weight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariantSpec {
+    pub weight: Option<FontWeight>,
+    pub style: Option<FontStyle>,
+    pub format: Option<String>,
+}
+
+impl VariantSpec {
+    /// Variant described by a downloaded font file name
+    fn from_file(path: &Path) -> Option<Self> {
+        let format = archive::font_format(path)?;
+        let info = archive::parse_font_file(path);
+        Some(Self { weight: info.weight, style: Some(info.style), format: Some(format) })
+    }
+    
+    /// Everything `variant` offers: its own weight, or each standard weight in a variable `wght` range
+    fn offered_by(variant: &FontVariant) -> Vec<Self> {
+        if !variant.is_variable() {
+            return vec![Self::from(variant)];
+        }
+        let spec = |weight| Self { weight, ..Self::from(variant) };
+        if !variant.axes.iter().any(|a| a.tag == "wght") {
+            return vec![spec(None)];
+        }
+        (1..=9)
+            .map(|n| FontWeight::from_numeric(n * 100))
+            .filter(|w| variant.covers_weight(*w))
+            .map(|w| spec(Some(w)))
+            .collect()
+    }
+    
+    /// Whether this offered variant satisfies `wanted`
+    fn covers(&self, wanted: &VariantSpec) -> bool {
+        let weight = wanted.weight.is_none_or(|w| self.weight.is_none_or(|o| o == w));
+        let style = wanted.style.is_none_or(|s| self.style == Some(s));
+        let format = wanted.format.as_ref()
+            .is_none_or(|f| self.format.as_ref().is_some_and(|o| o.eq_ignore_ascii_case(f)));
+        weight && style && format
+    }
+}
+
+impl From<&FontVariant> for VariantSpec {
+    fn from(v: &FontVariant) -> Self {
+        Self {
+            weight: Some(v.weight),
+            style: Some(v.style),
+            format: Some(v.file_format.clone()),
+        }
+    }
+}
+
+impl fmt::Display for VariantSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(weight) = self.weight {
+            parts.push(weight.to_numeric().to_string());
+        }
+        match self.style {
+            Some(FontStyle::Normal) => parts.push("normal".to_string()),
+            Some(FontStyle::Italic) => parts.push("italic".to_string()),
+            None => {}
+        }
+        if let Some(format) = &self.format {
+            parts.push(format.clone());
+        }
+        if parts.is_empty() {
+            f.write_str("any variant")
+        } else {
+            f.write_str(&parts.join(" "))
+        }
+    }
+}
+
+/// Every combination spelled out by `options`, with unset filters as "any"
+fn requested(options: &DownloadOptions) -> Vec<VariantSpec> {
+    let weights: Vec<Option<FontWeight>> = match &options.weights {
+        Some(weights) => weights.iter().copied().map(Some).collect(),
+        None => vec![None],
+    };
+    let styles: Vec<Option<FontStyle>> = match &options.styles {
+        Some(styles) => styles.iter().copied().map(Some).collect(),
+        None => vec![None],
+    };
+    let formats: Vec<Option<String>> = if options.formats.is_empty() {
+        vec![None]
+    } else {
+        options.formats.iter().map(|f| Some(f.to_lowercase())).collect()
+    };
+    
+    let mut specs = Vec::new();
+    for &weight in &weights {
+        for &style in &styles {
+            for format in &formats {
+                specs.push(VariantSpec { weight, style, format: format.clone() });
+            }
+        }
+    }
+    specs
+}
+
+/// Requested combinations that none of the `offered` variants cover
+fn unavailable(options: &DownloadOptions, offered: &[VariantSpec]) -> Vec<VariantSpec> {
+    requested(options)
+        .into_iter()
+        .filter(|wanted| !offered.iter().any(|o| o.covers(wanted)))
+        .collect()
+}
+
+/// One file to fetch for a per-variant download
+struct VariantJob {
+    url: String,
+    /// `<Family>-<Weight><Style>`, or just `<Family>` for a shared package
+    base: String,
+    /// Download name, unique among the jobs of one family
+    name: String,
+    format: String,
+    /// The URL serves exactly one variant, so its final path is known upfront
+    single: bool,
+}
+
+/// Downloads needed for the variants matching `options`
+///
+/// Variants sharing a URL (one package for the whole family) are fetched
+/// once under the family name. A variable variant replaces the static ones of
+/// its style when `options.prefer_variable` is set, and stands in for them
+/// when no static instance matches.
+fn variant_jobs(variants: &[FontVariant], prefix: &str, options: &DownloadOptions) -> Vec<VariantJob> {
+    let matching: Vec<&FontVariant> = variants.iter()
+        .filter(|v| v.file_url.is_some() && variant_wanted(v, options))
+        .collect();
+    let has = |variable: bool, style: FontStyle| {
+        matching.iter().any(|v| v.is_variable() == variable && v.style == style)
+    };
+    
+    let mut jobs: Vec<VariantJob> = Vec::new();
+    for variant in matching.iter().copied() {
+        let use_variable = has(true, variant.style) && (options.prefer_variable || !has(false, variant.style));
+        if variant.is_variable() != use_variable {
+            continue;
+        }
+        let Some(url) = &variant.file_url else { continue };
+        if let Some(job) = jobs.iter_mut().find(|j| &j.url == url) {
+            job.base = prefix.to_string();
+            job.name = prefix.to_string();
+            job.single = false;
+            continue;
+        }
+        
+        let info = FontFileInfo {
+            weight: (!variant.is_variable()).then_some(variant.weight),
+            style: variant.style,
+        };
+        let format = variant.file_format.to_lowercase();
+        let base = format!("{}-{}", prefix, info.suffix());
+        let name = if jobs.iter().any(|j| j.base == base) {
+            format!("{}-{}", base, format)
+        } else {
+            base.clone()
+        };
+        jobs.push(VariantJob { url: url.clone(), base, name, format, single: true });
+    }
+    jobs
+}
+
+/// Whether `variant` passes the format, weight and style filters of `options`
+///
+/// A variable variant passes the weight filter if its `wght` range holds
+/// every requested weight.
+fn variant_wanted(variant: &FontVariant, options: &DownloadOptions) -> bool {
+    let info = FontFileInfo { weight: None, style: variant.style };
+    if !archive::wanted(&variant.file_format, &info, options) {
+        return false;
+    }
+    match &options.weights {
+        Some(weights) if variant.is_variable() => weights.iter().all(|w| variant.covers_weight(*w)),
+        Some(weights) => weights.contains(&variant.weight),
+        None => true,
+    }
+}
+
+/// Font files in `dir` that match `options`
+async fn existing_fonts(dir: &Path, options: &DownloadOptions) -> Result<Vec<PathBuf>> {
+    let mut fonts = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if let Some(format) = archive::font_format(&path) {
+            if archive::wanted(&format, &archive::parse_font_file(&path), options) {
+                fonts.push(path);
+            }
+        }
+    }
+    fonts.sort();
+    Ok(fonts)
+}
+
+/// Size and SHA-256 of each downloaded file
+async fn describe(paths: Vec<PathBuf>, url: &str, skipped: bool) -> Result<Vec<DownloadedFile>> {
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let bytes = fs::read(&path).await?;
+        files.push(DownloadedFile {
+            size: bytes.len() as u64,
+            sha256: sha256_hex(&bytes),
+            path,
+            url: url.to_string(),
+            skipped,
+        });
+    }
+    Ok(files)
+}
+
+/// Lowercase hex SHA-256 digest
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Full length from a `Content-Range: bytes a-b/total` header
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response.headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Value for `If-Range` when resuming this response: a strong `ETag`,
+/// else `Last-Modified`
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+    let headers = response.headers();
+    headers.get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))?
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Apply the requested subsets to URLs that take a `subsets` parameter
+fn with_subsets(url: &str, subsets: &[String]) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    if subsets.is_empty() || !parsed.query_pairs().any(|(k, _)| k == "subsets") {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = parsed.query_pairs()
+        .map(|(k, v)| {
+            let v = if k == "subsets" { subsets.join(",") } else { v.into_owned() };
+            (k.into_owned(), v)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FontAxis;
+
+    #[tokio::test]
+    async fn test_restarts_partial_file_on_full_response() {
+        let base_url = crate::providers::scrape::serve(vec![("/font.ttf", 200, "fresh".to_string())]).await;
+        let dir = std::env::temp_dir().join(format!("dx-font-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("font.part"), "stale-bytes").unwrap();
+        std::fs::write(dir.join("font.part.validator"), "\"old\"").unwrap();
+
+        let downloader = FontDownloader::new().unwrap();
+        let path = downloader.download_file(&format!("{}/font.ttf", base_url), &dir, "font").await;
+        let contents = path.as_ref().map(|p| std::fs::read_to_string(p).unwrap());
+        let leftovers = dir.join("font.part").exists() || dir.join("font.part.validator").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(contents.unwrap(), "fresh");
+        assert!(!leftovers);
+    }
+
+    #[tokio::test]
+    async fn test_offline_download_fails() {
+        let config = Config { offline: true, ..Config::default() };
+        let downloader = FontDownloader::with_config(&config).unwrap();
+        let result = downloader.download_font(&FontProvider::GoogleFonts, "roboto", &DownloadOptions::default()).await;
+        assert!(matches!(result, Err(Error::Offline(_))));
+    }
+
+    #[test]
+    fn test_unavailable_combinations() {
+        let variant = |weight, style, format: &str| VariantSpec {
+            weight,
+            style: Some(style),
+            format: Some(format.to_string()),
+        };
+        let offered = vec![
+            variant(Some(FontWeight::Regular), FontStyle::Normal, "woff2"),
+            variant(Some(FontWeight::Regular), FontStyle::Italic, "woff2"),
+            variant(Some(FontWeight::Bold), FontStyle::Normal, "woff2"),
+        ];
+        let options = DownloadOptions {
+            formats: vec!["woff2".to_string()],
+            weights: Some(vec![FontWeight::Regular, FontWeight::Bold]),
+            styles: Some(vec![FontStyle::Italic]),
+            ..Default::default()
+        };
+        let missing: Vec<String> = unavailable(&options, &offered).iter().map(|s| s.to_string()).collect();
+        assert_eq!(missing, ["700 italic woff2"]);
+
+        // A variable font covers every weight
+        let offered = vec![variant(None, FontStyle::Italic, "ttf")];
+        let options = DownloadOptions { formats: vec!["ttf".to_string()], ..options };
+        assert!(unavailable(&options, &offered).is_empty());
+    }
+
+    #[test]
+    fn test_variant_jobs_prefer_variable() {
+        let variant = |weight, url: &str, axes: Vec<FontAxis>| FontVariant {
+            weight,
+            style: FontStyle::Normal,
+            file_url: Some(url.to_string()),
+            file_format: "woff2".to_string(),
+            axes,
+        };
+        let variants = vec![
+            variant(FontWeight::Regular, "https://example.com/400.woff2", Vec::new()),
+            variant(FontWeight::Bold, "https://example.com/700.woff2", Vec::new()),
+            variant(FontWeight::Regular, "https://example.com/wght.woff2", vec![FontAxis::new("wght", 300.0, 400.0, 800.0)]),
+        ];
+        let names = |options: &DownloadOptions| -> Vec<String> {
+            variant_jobs(&variants, "Inter", options).into_iter().map(|j| j.name).collect()
+        };
+
+        let options = DownloadOptions { formats: vec!["woff2".to_string()], ..Default::default() };
+        assert_eq!(names(&options), ["Inter-Regular", "Inter-Bold"]);
+        let options = DownloadOptions { prefer_variable: true, ..options };
+        assert_eq!(names(&options), ["Inter-Variable"]);
+        // Outside the `wght` range only the static instance helps
+        let options = DownloadOptions { weights: Some(vec![FontWeight::Black]), ..options };
+        assert!(names(&options).is_empty());
+        let options = DownloadOptions { weights: Some(vec![FontWeight::Medium]), prefer_variable: false, ..options };
+        assert_eq!(names(&options), ["Inter-Variable"]);
+    }
+
+    #[test]
+    fn test_with_subsets() {
+        let url = "https://gwfh.mranftl.com/api/fonts/inter?download=zip&subsets=latin&formats=woff2";
+        let subsets = vec!["latin".to_string(), "cyrillic".to_string()];
+        assert_eq!(
+            with_subsets(url, &subsets),
+            "https://gwfh.mranftl.com/api/fonts/inter?download=zip&subsets=latin%2Ccyrillic&formats=woff2"
+        );
+        assert_eq!(with_subsets("https://example.com/a.ttf", &subsets), "https://example.com/a.ttf");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
 }
@@ -0,0 +1,288 @@
+//! Error types for the dx-font library
+//!
+//! [`Error`] is what the public API returns, so downstream crates can match on
+//! a missing font or an HTTP failure instead of parsing messages.
+//! [`ProviderError`] is its cloneable, serializable summary used in
+//! per-provider outcome reports, so callers can tell "no matches" apart from
+//! "provider down".
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Result type used throughout the library
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by the dx-font library
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Provider not found: {provider}")]
+    ProviderNotFound { provider: String },
+
+    #[error("Font not found: {font_id} ({provider})")]
+    FontNotFound { provider: String, font_id: String },
+
+    #[error("{}HTTP {status} from {url}", prefix(provider))]
+    HttpStatus { provider: Option<String>, status: u16, url: String },
+
+    #[error("{}network error: {source}", prefix(provider))]
+    Network {
+        provider: Option<String>,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("{}could not decode response: {message}", prefix(provider))]
+    Decode { provider: Option<String>, message: String },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("{}request timed out", prefix(provider))]
+    Timeout { provider: Option<String> },
+
+    #[error("Unsupported font format: {format}")]
+    UnsupportedFormat { format: String },
+
+    /// The connection ended before `Content-Length` bytes arrived
+    #[error("Incomplete download from {url}: got {received} of {expected} bytes")]
+    Incomplete { url: String, expected: u64, received: u64 },
+
+    #[error("Could not read archive {path}: {message}")]
+    Archive { path: String, message: String },
+
+    #[error("Could not read font {path}: {message}")]
+    InvalidFont { path: String, message: String },
+
+    #[error("Invalid download list at {location}: {message}")]
+    InvalidList { location: String, message: String },
+
+    #[error("Invalid manifest {path}: {message}")]
+    Manifest { path: String, message: String },
+
+    #[error("Invalid cursor: {cursor} ({reason})")]
+    InvalidCursor { cursor: String, reason: String },
+
+    /// Data needed in offline mode is missing from the local snapshot
+    #[error("{0}")]
+    Offline(String),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+fn prefix(provider: &Option<String>) -> String {
+    provider.as_ref().map(|p| format!("{}: ", p)).unwrap_or_default()
+}
+
+impl Error {
+    /// Attach the provider name to errors that were raised without one
+    pub fn with_provider(mut self, name: &str) -> Self {
+        match &mut self {
+            Error::HttpStatus { provider, .. }
+            | Error::Network { provider, .. }
+            | Error::Decode { provider, .. }
+            | Error::Timeout { provider } => {
+                provider.get_or_insert_with(|| name.to_string());
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Shorthand for [`Error::FontNotFound`]
+    pub fn font_not_found(provider: &str, font_id: &str) -> Self {
+        Error::FontNotFound {
+            provider: provider.to_string(),
+            font_id: font_id.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ProviderNotFound { .. } | Error::FontNotFound { .. } => ErrorKind::NotFound,
+            Error::HttpStatus { .. } => ErrorKind::HttpStatus,
+            Error::Network { .. } | Error::Incomplete { .. } => ErrorKind::Network,
+            Error::Decode { .. } => ErrorKind::Parse,
+            Error::Timeout { .. } => ErrorKind::Timeout,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if let Some(status) = e.status() {
+            Error::HttpStatus {
+                provider: None,
+                status: status.as_u16(),
+                url: e.url().map(|u| u.to_string()).unwrap_or_default(),
+            }
+        } else if e.is_timeout() {
+            Error::Timeout { provider: None }
+        } else if e.is_decode() {
+            Error::Decode { provider: None, message: e.to_string() }
+        } else {
+            Error::Network { provider: None, source: e }
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Decode { provider: None, message: e.to_string() }
+    }
+}
+
+/// Why a provider could not answer
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProviderError {
+    #[error("network error: {message}")]
+    Network { message: String },
+
+    #[error("HTTP {status} from {url}")]
+    HttpStatus { status: u16, url: String },
+
+    #[error("could not parse response: {message}")]
+    Parse { message: String },
+
+    #[error("timed out: {message}")]
+    Timeout { message: String },
+
+    #[error("not found: {message}")]
+    NotFound { message: String },
+
+    #[error("{message}")]
+    Other { message: String },
+}
+
+/// The kind of a [`ProviderError`], without its details
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Network,
+    HttpStatus,
+    Parse,
+    Timeout,
+    NotFound,
+    Other,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ErrorKind::Network => "network",
+            ErrorKind::HttpStatus => "http status",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::NotFound => "not found",
+            ErrorKind::Other => "other",
+        };
+        f.write_str(name)
+    }
+}
+
+impl ProviderError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ProviderError::Network { .. } => ErrorKind::Network,
+            ProviderError::HttpStatus { .. } => ErrorKind::HttpStatus,
+            ProviderError::Parse { .. } => ErrorKind::Parse,
+            ProviderError::Timeout { .. } => ErrorKind::Timeout,
+            ProviderError::NotFound { .. } => ErrorKind::NotFound,
+            ProviderError::Other { .. } => ErrorKind::Other,
+        }
+    }
+
+    /// Classify an arbitrary provider failure
+    ///
+    /// Looks through the error chain for the HTTP client and JSON errors
+    /// providers bubble up with `?`.
+    pub fn classify(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<ProviderError>() {
+                return e.clone();
+            }
+            if let Some(e) = cause.downcast_ref::<Error>() {
+                return Self::from(e);
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                return Self::from(e);
+            }
+            if let Some(e) = cause.downcast_ref::<serde_json::Error>() {
+                return ProviderError::Parse { message: e.to_string() };
+            }
+        }
+
+        let message = err.to_string();
+        if message.to_lowercase().contains("not found") {
+            ProviderError::NotFound { message }
+        } else {
+            ProviderError::Other { message }
+        }
+    }
+}
+
+impl From<&Error> for ProviderError {
+    fn from(e: &Error) -> Self {
+        let message = e.to_string();
+        match e {
+            Error::HttpStatus { status, url, .. } => ProviderError::HttpStatus { status: *status, url: url.clone() },
+            Error::Network { source, .. } => ProviderError::Network { message: source.to_string() },
+            Error::Decode { message, .. } => ProviderError::Parse { message: message.clone() },
+            Error::Timeout { .. } => ProviderError::Timeout { message },
+            Error::ProviderNotFound { .. } | Error::FontNotFound { .. } => ProviderError::NotFound { message },
+            Error::Other(e) => ProviderError::classify(e),
+            _ => ProviderError::Other { message },
+        }
+    }
+}
+
+impl From<&reqwest::Error> for ProviderError {
+    fn from(e: &reqwest::Error) -> Self {
+        let message = e.to_string();
+        if let Some(status) = e.status() {
+            ProviderError::HttpStatus {
+                status: status.as_u16(),
+                url: e.url().map(|u| u.to_string()).unwrap_or_default(),
+            }
+        } else if e.is_timeout() {
+            ProviderError::Timeout { message }
+        } else if e.is_decode() {
+            ProviderError::Parse { message }
+        } else {
+            ProviderError::Network { message }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let parse = serde_json::from_str::<u32>("nope").unwrap_err();
+        let err = anyhow::Error::new(parse).context("Fontsource");
+        assert_eq!(ProviderError::classify(&err).kind(), ErrorKind::Parse);
+
+        let err = anyhow::anyhow!("Font not found: inter");
+        assert_eq!(ProviderError::classify(&err).kind(), ErrorKind::NotFound);
+
+        let json = serde_json::to_value(ProviderError::HttpStatus { status: 503, url: String::new() }).unwrap();
+        assert_eq!(json["kind"], "http_status");
+    }
+
+    #[test]
+    fn test_error_kind_and_provider() {
+        let err = Error::Timeout { provider: None }.with_provider("Fontsource");
+        assert_eq!(err.to_string(), "Fontsource: request timed out");
+        assert_eq!(ProviderError::from(&err).kind(), ErrorKind::Timeout);
+
+        let err = Error::font_not_found("DaFont", "inter");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(matches!(err, Error::FontNotFound { ref font_id, .. } if font_id == "inter"));
+    }
+}
@@ -0,0 +1,328 @@
+//! Shared HTTP request layer
+//!
+//! Every provider and the downloader send requests through [`HttpClient`],
+//! which retries transient failures (connect errors, 429 and 5xx) with jittered
+//! exponential backoff, honours `Retry-After`, and limits concurrency and
+//! request rate per host so scraped sites are not hit in bursts.
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderName, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep_until, Duration, Instant};
+
+use crate::config::HttpConfig;
+use crate::error::{Error, Result};
+
+/// HTTP client with retries and per-host limits
+///
+/// Cheap to clone; clones share the connection pool and the host limits.
+#[derive(Clone)]
+pub struct HttpClient {
+    inner: reqwest::Client,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    config: HttpConfig,
+    hosts: Mutex<HashMap<String, Arc<HostGate>>>,
+}
+
+/// Concurrency and pacing state for one host
+struct HostGate {
+    permits: Arc<Semaphore>,
+    /// Minimum spacing between request starts
+    interval: Option<Duration>,
+    next_start: tokio::sync::Mutex<Instant>,
+}
+
+impl HostGate {
+    fn new(max_in_flight: usize, requests_per_second: u32) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+            interval: (requests_per_second > 0).then(|| Duration::from_secs(1) / requests_per_second),
+            next_start: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait for a free slot and for this request's turn
+    async fn enter(&self) -> OwnedSemaphorePermit {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed");
+        if let Some(interval) = self.interval {
+            let start = {
+                let mut next = self.next_start.lock().await;
+                let start = (*next).max(Instant::now());
+                *next = start + interval;
+                start
+            };
+            sleep_until(start).await;
+        }
+        permit
+    }
+}
+
+impl HttpClient {
+    pub fn new(inner: reqwest::Client, config: HttpConfig) -> Self {
+        Self {
+            inner,
+            shared: Arc::new(Shared { config, hosts: Mutex::new(HashMap::new()) }),
+        }
+    }
+
+    /// Start a GET request
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.request(url, self.inner.get(url))
+    }
+
+    /// Start a HEAD request
+    pub fn head(&self, url: &str) -> RequestBuilder {
+        self.request(url, self.inner.head(url))
+    }
+
+    fn request(&self, url: &str, inner: reqwest::RequestBuilder) -> RequestBuilder {
+        RequestBuilder {
+            client: self.clone(),
+            url: url.to_string(),
+            inner,
+        }
+    }
+
+    fn gate(&self, url: &str) -> Arc<HostGate> {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut hosts = self.shared.hosts.lock().expect("host map poisoned");
+        let gate = hosts.entry(host).or_insert_with_key(|host| {
+            let (max_in_flight, rps) = self.shared.config.limits_for(host);
+            Arc::new(HostGate::new(max_in_flight, rps))
+        });
+        Arc::clone(gate)
+    }
+}
+
+/// A request that is retried and rate limited when sent
+pub struct RequestBuilder {
+    client: HttpClient,
+    url: String,
+    inner: reqwest::RequestBuilder,
+}
+
+impl RequestBuilder {
+    pub fn header(mut self, name: HeaderName, value: &str) -> Self {
+        self.inner = self.inner.header(name, value);
+        self
+    }
+
+    /// Send the request, retrying transient failures
+    ///
+    /// The host slot is held until the returned [`Response`] is dropped, so
+    /// body downloads count against the per-host concurrency too.
+    pub async fn send(self) -> Result<Response> {
+        let config = &self.client.shared.config;
+        let gate = self.client.gate(&self.url);
+        let mut attempt = 0;
+
+        loop {
+            // Bodies that cannot be cloned (streams) are sent exactly once
+            let Some(request) = self.inner.try_clone() else {
+                let permit = gate.enter().await;
+                let inner = self.inner.send().await?;
+                return Ok(Response { inner, permit });
+            };
+            let retries_left = attempt < config.max_retries;
+            let permit = gate.enter().await;
+
+            let delay = match request.send().await {
+                Ok(inner) if retries_left && is_retryable(inner.status()) => {
+                    let delay = retry_after(&inner)
+                        .unwrap_or_else(|| backoff(config, attempt, jitter()))
+                        .min(Duration::from_millis(config.retry_max_ms));
+                    tracing::debug!("HTTP {} from {}, retrying in {:?}", inner.status(), self.url, delay);
+                    delay
+                }
+                Ok(inner) => return Ok(Response { inner, permit }),
+                Err(e) if retries_left && e.is_connect() => {
+                    let delay = backoff(config, attempt, jitter());
+                    tracing::debug!("Connect error for {}: {}, retrying in {:?}", self.url, e, delay);
+                    delay
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            // Free the host slot while waiting
+            drop(permit);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// A response that keeps its host slot until dropped
+///
+/// Derefs to [`reqwest::Response`] for status and headers.
+pub struct Response {
+    inner: reqwest::Response,
+    permit: OwnedSemaphorePermit,
+}
+
+impl Deref for Response {
+    type Target = reqwest::Response;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Response {
+    /// Turn 4xx/5xx responses into [`Error::HttpStatus`]
+    pub fn error_for_status(self) -> Result<Self> {
+        let status = self.inner.status();
+        if status.is_client_error() || status.is_server_error() {
+            return Err(Error::HttpStatus {
+                provider: None,
+                status: status.as_u16(),
+                url: self.inner.url().to_string(),
+            });
+        }
+        Ok(self)
+    }
+
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        Ok(self.inner.json().await?)
+    }
+
+    pub async fn text(self) -> Result<String> {
+        Ok(self.inner.text().await?)
+    }
+
+    /// Body chunks; the host slot is released when the stream is dropped
+    pub fn bytes_stream(self) -> impl Stream<Item = Result<Bytes>> {
+        let permit = self.permit;
+        self.inner.bytes_stream().map(move |chunk| {
+            let _held = &permit;
+            chunk.map_err(Error::from)
+        })
+    }
+}
+
+/// Statuses worth retrying: rate limiting and server-side failures
+pub fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+}
+
+/// Exponential backoff for retry `attempt` (0-based)
+///
+/// `jitter` in `[0, 1)` spreads the delay over its upper half so clients that
+/// failed together do not retry together.
+pub fn backoff(config: &HttpConfig, attempt: u32, jitter: f64) -> Duration {
+    let base = config.retry_base_ms.saturating_mul(1u64 << attempt.min(20));
+    let capped = base.min(config.retry_max_ms);
+    Duration::from_millis(capped / 2 + (capped as f64 / 2.0 * jitter) as u64)
+}
+
+/// Delay requested by a `Retry-After` header
+///
+/// The caller caps it at `HttpConfig::retry_max_ms`.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, SystemTime::now())
+}
+
+/// `Retry-After` as delta-seconds or an HTTP-date, relative to `now`
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        // A date in the past means "retry now"
+        Err(_) => Some(parse_http_date(value)?.duration_since(now).unwrap_or_default()),
+    }
+}
+
+/// Parse an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let (_weekday, rest) = value.split_once(", ")?;
+    let parts: Vec<&str> = rest.split(' ').collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| m == month)? as u64 + 1;
+    let year: u64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|n| n.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (clock.next()??, clock.next()??, clock.next()??);
+    if year < 1970 || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Days since the epoch from the civil date (Howard Hinnant's algorithm)
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y / 400;
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    let secs = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// A random number in `[0, 1)`, good enough to de-synchronize retries
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    (hasher.finish() % 10_000) as f64 / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let config = HttpConfig { retry_base_ms: 500, retry_max_ms: 3_000, ..Default::default() };
+        assert_eq!(backoff(&config, 0, 0.0), Duration::from_millis(250));
+        assert_eq!(backoff(&config, 1, 0.0), Duration::from_millis(500));
+        assert_eq!(backoff(&config, 1, 0.999), Duration::from_millis(999));
+        assert_eq!(backoff(&config, 10, 0.0), Duration::from_millis(1_500));
+
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_777); // Sun, 06 Nov 1994 08:49:37 GMT
+        assert_eq!(parse_retry_after(" 120 ", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:51:37 GMT", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sat, 05 Nov 1994 08:49:37 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Thu, 29 Feb 2024 00:00:00 GMT", UNIX_EPOCH), Some(Duration::from_secs(1_709_164_800)));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_host_gate_spaces_requests() {
+        let gate = HostGate::new(4, 20);
+        let start = Instant::now();
+        for _ in 0..3 {
+            drop(gate.enter().await);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,534 @@
+//! Font file inspection
+//!
+//! Reads the metadata that providers often guess at straight from the font:
+//! names, weight class, italic flag, designer, license, version, glyph count,
+//! Unicode coverage and variation axes. TTF/OTF (and collections) are parsed
+//! directly; WOFF and WOFF2 are first unpacked into a plain sfnt.
+//!
+//! WOFF2 may store `glyf`, `loca` and `hmtx` in a transformed layout. None of
+//! the tables read here depend on them, so transformed tables are dropped
+//! instead of reconstructed.
+
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::Path;
+use ttf_parser::name_id;
+
+use crate::error::{Error, Result};
+use crate::models::{FontAxis, FontLicense};
+
+/// Windows English (United States), preferred when a name has several languages
+const ENGLISH_US: u16 = 0x0409;
+
+/// Google Fonts subsets with the characters a font needs to support them
+///
+/// A subset counts as supported when at least 90% of its characters are mapped.
+const SUBSET_CHARS: &[(&str, &[(u32, u32)])] = &[
+    ("latin", &[(0x0041, 0x005A), (0x0061, 0x007A)]),
+    ("latin-ext", &[(0x0100, 0x017F)]),
+    ("cyrillic", &[(0x0410, 0x044F)]),
+    ("cyrillic-ext", &[(0x0460, 0x0481), (0x048A, 0x04FF)]),
+    ("greek", &[(0x0391, 0x03A1), (0x03A3, 0x03A9), (0x03B1, 0x03C9)]),
+    ("greek-ext", &[(0x1F00, 0x1F15), (0x1F18, 0x1F1D), (0x1F20, 0x1F45)]),
+    ("vietnamese", &[(0x1EA0, 0x1EF9)]),
+    ("hebrew", &[(0x05D0, 0x05EA)]),
+    ("arabic", &[(0x0621, 0x063A), (0x0641, 0x064A)]),
+    ("devanagari", &[(0x0905, 0x0939)]),
+    ("thai", &[(0x0E01, 0x0E2E)]),
+];
+
+/// Tags of the WOFF2 known-table index
+const WOFF2_KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+    b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+    b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+    b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+    b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+/// Metadata read from a font file
+#[derive(Debug, Clone, Serialize)]
+pub struct FontInfo {
+    /// Container format: `ttf`, `otf`, `ttc`, `woff` or `woff2`
+    pub format: String,
+    /// Typographic family name, falling back to the legacy family name
+    pub family: Option<String>,
+    pub subfamily: Option<String>,
+    pub full_name: Option<String>,
+    pub postscript_name: Option<String>,
+    pub version: Option<String>,
+    pub designer: Option<String>,
+    pub manufacturer: Option<String>,
+    pub copyright: Option<String>,
+    pub license: Option<String>,
+    pub license_url: Option<String>,
+    /// `usWeightClass` from `OS/2` (100-900)
+    pub weight_class: u16,
+    pub italic: bool,
+    pub glyph_count: u16,
+    /// Number of Unicode code points mapped by `cmap`
+    pub codepoint_count: usize,
+    /// Google Fonts subsets the character map supports
+    pub subsets: Vec<String>,
+    pub axes: Vec<FontAxis>,
+}
+
+impl FontInfo {
+    pub fn is_variable(&self) -> bool {
+        !self.axes.is_empty()
+    }
+    
+    /// The license named by the license description or URL entries, if recognized
+    pub fn license_kind(&self) -> Option<FontLicense> {
+        let text = [&self.license, &self.license_url]
+            .iter()
+            .filter_map(|s| s.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ");
+        FontLicense::from_text(&text)
+    }
+}
+
+/// Inspect the font file at `path`
+pub fn inspect(path: &Path) -> Result<FontInfo> {
+    let data = std::fs::read(path)?;
+    inspect_bytes(&data).map_err(|message| Error::InvalidFont {
+        path: path.display().to_string(),
+        message,
+    })
+}
+
+/// Inspect a font held in memory (TTF, OTF, TTC, WOFF or WOFF2)
+pub fn inspect_bytes(data: &[u8]) -> std::result::Result<FontInfo, String> {
+    let (format, sfnt) = match data.get(..4) {
+        Some(b"wOFF") => ("woff", Cow::Owned(unwrap_woff(data)?)),
+        Some(b"wOF2") => ("woff2", Cow::Owned(unwrap_woff2(data)?)),
+        Some(b"OTTO") => ("otf", Cow::Borrowed(data)),
+        Some(b"ttcf") => ("ttc", Cow::Borrowed(data)),
+        Some([0, 1, 0, 0] | b"true") => ("ttf", Cow::Borrowed(data)),
+        _ => return Err("not a TrueType, OpenType, WOFF or WOFF2 font".to_string()),
+    };
+    let face = ttf_parser::Face::parse(&sfnt, 0).map_err(|e| e.to_string())?;
+
+    let mut codepoints = Vec::new();
+    if let Some(cmap) = face.tables().cmap {
+        for subtable in cmap.subtables {
+            if subtable.is_unicode() {
+                subtable.codepoints(|c| codepoints.push(c));
+            }
+        }
+    }
+    codepoints.sort_unstable();
+    codepoints.dedup();
+
+    let axes = face.variation_axes()
+        .into_iter()
+        .map(|axis| FontAxis {
+            tag: axis.tag.to_string(),
+            name: name(&face, axis.name_id),
+            min: axis.min_value,
+            default: axis.def_value,
+            max: axis.max_value,
+        })
+        .collect();
+
+    Ok(FontInfo {
+        format: format.to_string(),
+        family: name(&face, name_id::TYPOGRAPHIC_FAMILY).or_else(|| name(&face, name_id::FAMILY)),
+        subfamily: name(&face, name_id::TYPOGRAPHIC_SUBFAMILY).or_else(|| name(&face, name_id::SUBFAMILY)),
+        full_name: name(&face, name_id::FULL_NAME),
+        postscript_name: name(&face, name_id::POST_SCRIPT_NAME),
+        version: name(&face, name_id::VERSION),
+        designer: name(&face, name_id::DESIGNER),
+        manufacturer: name(&face, name_id::MANUFACTURER),
+        copyright: name(&face, name_id::COPYRIGHT_NOTICE),
+        license: name(&face, name_id::LICENSE),
+        license_url: name(&face, name_id::LICENSE_URL),
+        weight_class: face.weight().to_number(),
+        italic: face.is_italic(),
+        glyph_count: face.number_of_glyphs(),
+        codepoint_count: codepoints.len(),
+        subsets: supported_subsets(&codepoints),
+        axes,
+    })
+}
+
+/// Subsets whose characters are (nearly) all in the sorted `codepoints`
+pub fn supported_subsets(codepoints: &[u32]) -> Vec<String> {
+    SUBSET_CHARS.iter()
+        .filter(|(_, ranges)| {
+            let total: u32 = ranges.iter().map(|(start, end)| end - start + 1).sum();
+            let mapped = ranges.iter()
+                .flat_map(|&(start, end)| start..=end)
+                .filter(|c| codepoints.binary_search(c).is_ok())
+                .count() as u32;
+            mapped * 10 >= total * 9
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Entry of `name`, preferring US English
+fn name(face: &ttf_parser::Face, id: u16) -> Option<String> {
+    let mut candidates: Vec<_> = face.names()
+        .into_iter()
+        .filter(|n| n.name_id == id)
+        .collect();
+    candidates.sort_by_key(|n| n.language_id != ENGLISH_US);
+    candidates.iter()
+        .find_map(|n| n.to_string())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Rebuild the sfnt from a WOFF 1.0 file
+fn unwrap_woff(data: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let mut r = Reader::new(data);
+    r.skip(4)?;
+    let flavor = r.u32()?;
+    r.skip(4)?;
+    let num_tables = r.u16()?;
+    if num_tables == 0 {
+        return Err("WOFF file has no tables".to_string());
+    }
+    r.skip(30)?;
+
+    let mut tables = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        let tag = r.tag()?;
+        let offset = r.u32()? as usize;
+        let comp_length = r.u32()? as usize;
+        let orig_length = r.u32()? as usize;
+        r.skip(4)?;
+
+        let stored = data.get(offset..offset + comp_length).ok_or("table data out of bounds")?;
+        let table = if comp_length < orig_length {
+            let mut out = Vec::with_capacity(orig_length);
+            flate2::read::ZlibDecoder::new(stored)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("could not decompress '{}': {}", tag_name(&tag), e))?;
+            out
+        } else {
+            stored.to_vec()
+        };
+        tables.push((tag, table));
+    }
+    Ok(build_sfnt(flavor, tables))
+}
+
+/// Rebuild an sfnt with the untransformed tables of a WOFF2 file
+fn unwrap_woff2(data: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let mut r = Reader::new(data);
+    r.skip(4)?;
+    let flavor = r.u32()?;
+    if flavor == u32::from_be_bytes(*b"ttcf") {
+        return Err("WOFF2 font collections are not supported".to_string());
+    }
+    r.skip(4)?;
+    let num_tables = r.u16()?;
+    if num_tables == 0 {
+        return Err("WOFF2 file has no tables".to_string());
+    }
+    r.skip(6)?;
+    let compressed_size = r.u32()? as usize;
+    r.skip(24)?;
+
+    // (tag, length in the decompressed stream, transformed)
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        let flags = r.u8()?;
+        let tag = match flags & 0x3f {
+            63 => r.tag()?,
+            index => *WOFF2_KNOWN_TAGS[index as usize],
+        };
+        let orig_length = r.base128()?;
+        let version = flags >> 6;
+        // `glyf` and `loca` use version 3 for "not transformed", other tables 0
+        let transformed = if &tag == b"glyf" || &tag == b"loca" { version != 3 } else { version != 0 };
+        let length = if transformed { r.base128()? } else { orig_length };
+        entries.push((tag, length as usize, transformed));
+    }
+
+    let compressed = data.get(r.pos..r.pos + compressed_size).ok_or("compressed data out of bounds")?;
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .read_to_end(&mut stream)
+        .map_err(|e| format!("could not decompress tables: {}", e))?;
+
+    let mut offset = 0;
+    let mut tables = Vec::with_capacity(entries.len());
+    for (tag, length, transformed) in entries {
+        let table = stream.get(offset..offset + length).ok_or("table data out of bounds")?;
+        offset += length;
+        if !transformed {
+            tables.push((tag, table.to_vec()));
+        }
+    }
+    Ok(build_sfnt(flavor, tables))
+}
+
+/// Assemble an sfnt from its tables; checksums are left at zero
+fn build_sfnt(flavor: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|t| t.0);
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&num_tables.saturating_mul(16).saturating_sub(search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, table) in &tables {
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (_, table) in &tables {
+        out.extend_from_slice(table);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    out
+}
+
+fn tag_name(tag: &[u8; 4]) -> String {
+    String::from_utf8_lossy(tag).into_owned()
+}
+
+/// Big-endian reader over a byte slice
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take<const N: usize>(&mut self) -> std::result::Result<[u8; N], String> {
+        let bytes = self.data.get(self.pos..self.pos + N).ok_or("unexpected end of header")?;
+        self.pos += N;
+        Ok(bytes.try_into().expect("slice has length N"))
+    }
+
+    fn skip(&mut self, n: usize) -> std::result::Result<(), String> {
+        if self.pos + n > self.data.len() {
+            return Err("unexpected end of header".to_string());
+        }
+        self.pos += n;
+        Ok(())
+    }
+
+    fn u8(&mut self) -> std::result::Result<u8, String> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> std::result::Result<u16, String> {
+        Ok(u16::from_be_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> std::result::Result<u32, String> {
+        Ok(u32::from_be_bytes(self.take()?))
+    }
+
+    fn tag(&mut self) -> std::result::Result<[u8; 4], String> {
+        self.take()
+    }
+
+    /// WOFF2 `UIntBase128`
+    fn base128(&mut self) -> std::result::Result<u32, String> {
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            if i == 0 && byte == 0x80 {
+                return Err("invalid UIntBase128 (leading zeros)".to_string());
+            }
+            if value & 0xFE00_0000 != 0 {
+                return Err("invalid UIntBase128 (overflow)".to_string());
+            }
+            value = (value << 7) | u32::from(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("invalid UIntBase128 (too long)".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A minimal variable font mapping U+0020-U+007E
+    fn test_font() -> Vec<u8> {
+        let mut head = vec![0u8; 54];
+        head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+
+        let mut hhea = vec![0u8; 36];
+        hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend_from_slice(&96u16.to_be_bytes());
+
+        let mut os2 = vec![0u8; 78];
+        os2[4..6].copy_from_slice(&600u16.to_be_bytes());
+        os2[62..64].copy_from_slice(&1u16.to_be_bytes());
+
+        let mut cmap = Vec::new();
+        for v in [0u16, 1, 3, 10] {
+            cmap.extend_from_slice(&v.to_be_bytes());
+        }
+        cmap.extend_from_slice(&12u32.to_be_bytes());
+        for v in [12u16, 0] {
+            cmap.extend_from_slice(&v.to_be_bytes());
+        }
+        for v in [28u32, 0, 1, 0x20, 0x7E, 1] {
+            cmap.extend_from_slice(&v.to_be_bytes());
+        }
+
+        let strings: [(u16, &str); 4] = [(1, "Test Sans"), (2, "SemiBold Italic"), (9, "Jane Doe"), (256, "Weight")];
+        let mut name = Vec::new();
+        let mut storage = Vec::new();
+        for v in [0u16, strings.len() as u16, 6 + 12 * strings.len() as u16] {
+            name.extend_from_slice(&v.to_be_bytes());
+        }
+        for (id, text) in strings {
+            let encoded: Vec<u8> = text.encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+            for v in [3u16, 1, ENGLISH_US, id, encoded.len() as u16, storage.len() as u16] {
+                name.extend_from_slice(&v.to_be_bytes());
+            }
+            storage.extend(encoded);
+        }
+        name.extend(storage);
+
+        let mut fvar = Vec::new();
+        for v in [1u16, 0, 16, 2, 1, 20, 0, 8] {
+            fvar.extend_from_slice(&v.to_be_bytes());
+        }
+        fvar.extend_from_slice(b"wght");
+        for v in [100i32, 600, 900] {
+            fvar.extend_from_slice(&(v << 16).to_be_bytes());
+        }
+        for v in [0u16, 256] {
+            fvar.extend_from_slice(&v.to_be_bytes());
+        }
+
+        build_sfnt(0x0001_0000, vec![
+            (*b"head", head), (*b"hhea", hhea), (*b"maxp", maxp), (*b"OS/2", os2),
+            (*b"cmap", cmap), (*b"name", name), (*b"fvar", fvar),
+        ])
+    }
+
+    /// Wrap `sfnt` as a WOFF with every table zlib-compressed
+    fn to_woff(sfnt: &[u8]) -> Vec<u8> {
+        let face = ttf_parser::RawFace::parse(sfnt, 0).unwrap();
+        let records: Vec<_> = face.table_records.into_iter().collect();
+        let mut directory = Vec::new();
+        let mut body = Vec::new();
+        let data_start = 44 + 20 * records.len();
+        for record in &records {
+            let table = &sfnt[record.offset as usize..(record.offset + record.length) as usize];
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(table).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let stored = if compressed.len() < table.len() { compressed } else { table.to_vec() };
+
+            directory.extend_from_slice(&record.tag.to_bytes());
+            for v in [(data_start + body.len()) as u32, stored.len() as u32, table.len() as u32, 0] {
+                directory.extend_from_slice(&v.to_be_bytes());
+            }
+            body.extend(stored);
+            body.resize(body.len().next_multiple_of(4), 0);
+        }
+
+        let mut woff = b"wOFF".to_vec();
+        woff.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        woff.extend_from_slice(&((data_start + body.len()) as u32).to_be_bytes());
+        woff.extend_from_slice(&(records.len() as u16).to_be_bytes());
+        woff.resize(44, 0);
+        woff.extend(directory);
+        woff.extend(body);
+        woff
+    }
+
+    /// Wrap `sfnt` as a WOFF2 without table transforms
+    fn to_woff2(sfnt: &[u8]) -> Vec<u8> {
+        let face = ttf_parser::RawFace::parse(sfnt, 0).unwrap();
+        let mut directory = Vec::new();
+        let mut stream = Vec::new();
+        let mut count = 0u16;
+        for record in face.table_records {
+            let tag = record.tag.to_bytes();
+            match WOFF2_KNOWN_TAGS.iter().position(|t| **t == tag) {
+                Some(index) => directory.push(index as u8),
+                None => {
+                    directory.push(63);
+                    directory.extend_from_slice(&tag);
+                }
+            }
+            // Lengths in the test font fit in one or two base-128 bytes
+            let length = record.length;
+            if length >= 128 {
+                directory.push(0x80 | (length >> 7) as u8);
+            }
+            directory.push((length & 0x7f) as u8);
+            stream.extend_from_slice(&sfnt[record.offset as usize..(record.offset + length) as usize]);
+            count += 1;
+        }
+        let mut compressed = Vec::new();
+        brotli::BrotliCompress(&mut &stream[..], &mut compressed, &Default::default()).unwrap();
+
+        let mut woff2 = b"wOF2".to_vec();
+        woff2.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        woff2.extend_from_slice(&0u32.to_be_bytes());
+        woff2.extend_from_slice(&count.to_be_bytes());
+        woff2.extend_from_slice(&[0; 6]);
+        woff2.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        woff2.resize(48, 0);
+        woff2.extend(directory);
+        woff2.extend(compressed);
+        woff2
+    }
+
+    #[test]
+    fn test_inspect_sfnt_and_woff() {
+        let sfnt = test_font();
+        for (data, format) in [(sfnt.clone(), "ttf"), (to_woff(&sfnt), "woff"), (to_woff2(&sfnt), "woff2")] {
+            let info = inspect_bytes(&data).unwrap();
+            assert_eq!(info.format, format);
+            assert_eq!(info.family.as_deref(), Some("Test Sans"));
+            assert_eq!(info.subfamily.as_deref(), Some("SemiBold Italic"));
+            assert_eq!(info.designer.as_deref(), Some("Jane Doe"));
+            assert_eq!(info.weight_class, 600);
+            assert!(info.italic);
+            assert_eq!(info.glyph_count, 96);
+            assert_eq!(info.codepoint_count, 95);
+            assert_eq!(info.subsets, ["latin"]);
+            assert_eq!(info.axes, [FontAxis {
+                tag: "wght".to_string(),
+                name: Some("Weight".to_string()),
+                min: 100.0,
+                default: 600.0,
+                max: 900.0,
+            }]);
+        }
+        assert!(inspect_bytes(b"not a font").is_err());
+        for (magic, header_len) in [(b"wOFF", 44), (b"wOF2", 48)] {
+            let mut empty = magic.to_vec();
+            empty.resize(header_len, 0);
+            assert!(inspect_bytes(&empty).is_err());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! System font installation (Linux)
+//!
+//! `dx-font install` downloads a family's TTF/OTF files into
+//! `$XDG_DATA_HOME/fonts/dx-font/<Family>/`, where fontconfig picks them up,
+//! and refreshes the fontconfig cache when `fc-cache` is available. What was
+//! installed is recorded in `installed.json` under the dx-font data directory
+//! so fonts can be listed and removed again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs;
+
+use crate::archive;
+use crate::config::Config;
+use crate::download::FontDownloader;
+use crate::error::{Error, Result};
+use crate::models::{DownloadOptions, FontProvider};
+
+/// Formats the desktop font stack can use
+const INSTALL_FORMATS: &[&str] = &["ttf", "otf"];
+
+/// A family installed by dx-font
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledFont {
+    pub font_id: String,
+    /// Display name (`Open Sans`)
+    pub name: String,
+    pub provider: FontProvider,
+    /// Provider's `last_modified` date at install time
+    pub version: Option<String>,
+    /// Unix timestamp of the install
+    pub installed_at: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// The `installed.json` registry, keyed by family directory name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Registry {
+    fonts: BTreeMap<String, InstalledFont>,
+}
+
+/// Installs families into the user's font directory
+pub struct FontInstaller {
+    downloader: FontDownloader,
+    /// `$XDG_DATA_HOME/fonts/dx-font`
+    fonts_dir: PathBuf,
+    registry_path: PathBuf,
+    /// Run `fc-cache` after changes
+    refresh_cache: bool,
+}
+
+impl FontInstaller {
+    /// Installer for the current user's font directory
+    pub fn with_config(config: &Config) -> Result<Self> {
+        if !cfg!(target_os = "linux") {
+            return Err(Error::Other(anyhow::anyhow!("Installing fonts is only supported on Linux")));
+        }
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| Error::Other(anyhow::anyhow!("Could not determine the user data directory")))?;
+        Ok(Self {
+            refresh_cache: true,
+            ..Self::with_dirs(
+                FontDownloader::with_config(config)?,
+                data_dir.join("fonts").join("dx-font"),
+                data_dir.join("dx-font").join("installed.json"),
+            )
+        })
+    }
+
+    /// Installer using explicit locations
+    ///
+    /// The fontconfig cache is not refreshed, since fontconfig does not scan
+    /// arbitrary directories.
+    pub fn with_dirs(downloader: FontDownloader, fonts_dir: PathBuf, registry_path: PathBuf) -> Self {
+        Self { downloader, fonts_dir, registry_path, refresh_cache: false }
+    }
+
+    pub fn fonts_dir(&self) -> &Path {
+        &self.fonts_dir
+    }
+
+    /// Download `font_id` from `provider` and install its TTF/OTF files
+    ///
+    /// Reinstalling an installed family replaces its registry entry; files
+    /// already present are kept unless `force` is set.
+    pub async fn install(&self, provider: &FontProvider, font_id: &str, force: bool) -> Result<InstalledFont> {
+        let options = DownloadOptions {
+            output_dir: self.fonts_dir.clone(),
+            formats: INSTALL_FORMATS.iter().map(|f| f.to_string()).collect(),
+            subsets: Vec::new(),
+            force,
+            ..Default::default()
+        };
+        let result = self.downloader.download_font(provider, font_id, &options).await?;
+        let files: Vec<PathBuf> = result.files.into_iter().map(|f| f.path).collect();
+        if !files.iter().any(|f| archive::font_format(f).is_some()) {
+            return Err(Error::UnsupportedFormat {
+                format: format!("{} offers no TTF or OTF files for {}", provider.name(), font_id),
+            });
+        }
+
+        let name = result.family.unwrap_or_else(|| font_id.to_string());
+        let font = InstalledFont {
+            font_id: font_id.to_string(),
+            name: name.clone(),
+            provider: provider.clone(),
+            version: result.last_modified,
+            installed_at: unix_now(),
+            files,
+        };
+
+        let mut registry = self.load().await?;
+        registry.fonts.insert(archive::family_dir_name(&name), font.clone());
+        self.save(&registry).await?;
+        self.refresh_font_cache().await;
+        Ok(font)
+    }
+
+    /// Remove an installed family by font id, display name or directory name
+    pub async fn uninstall(&self, font: &str) -> Result<InstalledFont> {
+        let mut registry = self.load().await?;
+        let key = registry.fonts.iter()
+            .find(|(dir, f)| {
+                dir.eq_ignore_ascii_case(font) || f.font_id.eq_ignore_ascii_case(font) || f.name.eq_ignore_ascii_case(font)
+            })
+            .map(|(dir, _)| dir.clone())
+            .ok_or_else(|| Error::FontNotFound { provider: "installed fonts".to_string(), font_id: font.to_string() })?;
+        let removed = registry.fonts.remove(&key).expect("key was just found");
+
+        for file in &removed.files {
+            match fs::remove_file(file).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        // Only removes the directory if nothing else was put there
+        let _ = fs::remove_dir(self.fonts_dir.join(&key)).await;
+
+        self.save(&registry).await?;
+        self.refresh_font_cache().await;
+        Ok(removed)
+    }
+
+    /// Installed families in directory name order
+    pub async fn installed(&self) -> Result<Vec<InstalledFont>> {
+        Ok(self.load().await?.fonts.into_values().collect())
+    }
+
+    async fn load(&self) -> Result<Registry> {
+        match fs::read(&self.registry_path).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, registry: &Registry) -> Result<()> {
+        if let Some(parent) = self.registry_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let tmp_path = self.registry_path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(registry)?).await?;
+        fs::rename(&tmp_path, &self.registry_path).await?;
+        Ok(())
+    }
+
+    /// Run `fc-cache` on the font directory; a missing or failing `fc-cache` is not an error
+    async fn refresh_font_cache(&self) {
+        if !self.refresh_cache {
+            return;
+        }
+        match tokio::process::Command::new("fc-cache").arg("-f").arg(&self.fonts_dir).output().await {
+            Ok(output) if !output.status.success() => {
+                tracing::warn!("fc-cache failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Skipping font cache refresh: {}", e),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_registry_round_trip_and_uninstall() {
+        let root = std::env::temp_dir().join(format!("dx-font-install-{}", std::process::id()));
+        let installer = FontInstaller::with_dirs(
+            FontDownloader::new().unwrap(),
+            root.join("fonts"),
+            root.join("installed.json"),
+        );
+        let file = root.join("fonts/OpenSans/OpenSans-Regular.ttf");
+        fs::create_dir_all(file.parent().unwrap()).await.unwrap();
+        fs::write(&file, b"font").await.unwrap();
+
+        let mut registry = Registry::default();
+        registry.fonts.insert("OpenSans".to_string(), InstalledFont {
+            font_id: "open-sans".to_string(),
+            name: "Open Sans".to_string(),
+            provider: FontProvider::GoogleFonts,
+            version: Some("2024-01-01".to_string()),
+            installed_at: 0,
+            files: vec![file.clone()],
+        });
+        installer.save(&registry).await.unwrap();
+        assert_eq!(installer.installed().await.unwrap().len(), 1);
+
+        let removed = installer.uninstall("Open Sans").await.unwrap();
+        assert_eq!(removed.font_id, "open-sans");
+        assert!(!file.exists());
+        assert!(!root.join("fonts/OpenSans").exists());
+        assert!(installer.installed().await.unwrap().is_empty());
+        assert!(installer.uninstall("open-sans").await.is_err());
+
+        fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -9,9 +9,21 @@
 //!
 //! ## Features
 //! - Blazing fast parallel search across all providers
+//! - Streaming results as each provider responds
+//! - Relevance-ranked, typo-tolerant results over the merged catalog
+//! - Cross-provider deduplication into canonical font families
 //! - Concurrent downloads with progress indication
+//! - Retries with backoff and per-host rate limits for every request
+//! - Automatic archive extraction into a per-family font layout
+//! - Project manifest (`dx-font.toml`) with a pinned lockfile (`dx-font.lock`)
 //! - CDN URL generation for font preview and usage
+//! - `@font-face` stylesheets for self-hosted downloads
+//! - Installation into the user font directory on Linux
+//! - Font file inspection (names, weights, coverage, axes) for TTF/OTF/WOFF/WOFF2
+//! - License reports (`FONT-LICENSES.md`, JSON, SPDX) for bundled fonts
 //! - Support for multiple font formats (TTF, OTF, WOFF, WOFF2)
+//! - On-disk catalog cache with TTL and stale-while-revalidate
+//! - Offline Google Fonts metadata from a local google/fonts clone or tarball
 
 pub mod providers;
 pub mod models;
@@ -20,8 +32,24 @@ pub mod download;
 pub mod cli;
 pub mod config;
 pub mod cdn;
+pub mod cache;
+pub mod error;
+pub mod ranking;
+pub mod dedup;
+pub mod pagination;
+pub mod stream;
+pub mod archive;
+pub mod http;
+pub mod batch;
+pub mod manifest;
+pub mod css;
+pub mod install;
+pub mod inspect;
+pub mod licenses;
 
 pub use models::{Font, FontFamily, FontProvider, FontWeight, FontStyle};
 pub use search::FontSearch;
 pub use download::FontDownloader;
 pub use cdn::{CdnUrlGenerator, FontCdnUrls, CdnProvider};
+pub use cache::{CachePolicy, CatalogCache};
+pub use error::{Error, Result};
@@ -0,0 +1,337 @@
+//! License reports for bundled fonts
+//!
+//! Apps that ship fonts must ship their license texts too. This module
+//! gathers, for every family in a font directory, lockfile or install
+//! registry, the license text (from the license files that archives carry,
+//! else name-table IDs 13 and 14), copyright and designer, and renders them
+//! as `FONT-LICENSES.md`, JSON or an SPDX 2.3 tag-value document.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::error::Result;
+use crate::inspect::{self, FontInfo};
+use crate::install::InstalledFont;
+use crate::manifest::Lockfile;
+use crate::models::{spdx_ident, FontFamily, FontLicense, FontProvider, LicenseSource};
+
+/// Font files believed to be one family, before their licenses are read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontSet {
+    /// Known family name; otherwise read from the name table
+    pub family: Option<String>,
+    pub provider: Option<FontProvider>,
+    pub font_id: Option<String>,
+    /// Where the files were downloaded from
+    pub source_url: Option<String>,
+    pub files: Vec<PathBuf>,
+}
+
+/// License details of one family
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseEntry {
+    pub family: String,
+    pub provider: Option<FontProvider>,
+    pub font_id: Option<String>,
+    pub license: Option<FontLicense>,
+    /// SPDX id (`OFL-1.1`), `LicenseRef-...` or `NOASSERTION`
+    pub license_id: String,
+    pub license_source: Option<LicenseSource>,
+    pub copyright: Option<String>,
+    pub designer: Option<String>,
+    pub source_url: Option<String>,
+    pub license_url: Option<String>,
+    pub license_text: Option<String>,
+    /// The license file the text was read from, or `name table`
+    pub license_text_from: Option<String>,
+    pub files: Vec<PathBuf>,
+}
+
+impl LicenseEntry {
+    fn set_license(&mut self, license: FontLicense, source: LicenseSource) {
+        self.license_id = license.spdx_id();
+        self.license = Some(license);
+        self.license_source = Some(source);
+    }
+
+    /// Fill gaps from the provider's details; what the files say wins
+    pub fn apply_family(&mut self, family: &FontFamily) {
+        if self.license.is_none() {
+            if let Some(license) = &family.license {
+                self.set_license(license.clone(), family.license_source);
+            }
+        }
+        if self.license_url.is_none() {
+            self.license_url = self.license.as_ref().and_then(FontLicense::reference_url);
+        }
+        self.designer = self.designer.take().or_else(|| family.designer.clone());
+        self.source_url = self.source_url.take().or_else(|| family.download_url.clone());
+    }
+}
+
+/// Font sets under `root`, one per family name and directory
+pub fn scan_dir(root: &Path) -> Result<Vec<FontSet>> {
+    let mut files = Vec::new();
+    walk(root, &mut files)?;
+    files.sort();
+
+    let mut sets: BTreeMap<(PathBuf, String), FontSet> = BTreeMap::new();
+    for path in files.into_iter().filter(|p| archive::font_format(p).is_some()) {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let family = inspect::inspect(&path).ok()
+            .and_then(|info| info.family)
+            .unwrap_or_else(|| dir_name(&dir));
+        let set = sets.entry((dir, family.clone())).or_insert_with(|| FontSet {
+            family: Some(family),
+            ..Default::default()
+        });
+        set.files.push(path);
+    }
+    Ok(sets.into_values().collect())
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Font sets for the families pinned in a lockfile; paths are relative to `base`
+pub fn from_lockfile(lock: &Lockfile, base: &Path) -> Vec<FontSet> {
+    lock.fonts.iter()
+        .map(|font| FontSet {
+            family: None,
+            provider: font.provider.parse().ok(),
+            font_id: Some(font.id.clone()),
+            source_url: font.files.first().map(|f| f.url.clone()),
+            files: font.files.iter().map(|f| base.join(&f.path)).collect(),
+        })
+        .collect()
+}
+
+/// Font sets for the families installed with `dx-font install`
+pub fn from_installed(fonts: &[InstalledFont]) -> Vec<FontSet> {
+    fonts.iter()
+        .map(|font| FontSet {
+            family: Some(font.name.clone()),
+            provider: Some(font.provider.clone()),
+            font_id: Some(font.font_id.clone()),
+            source_url: None,
+            files: font.files.clone(),
+        })
+        .collect()
+}
+
+/// Read the license details of `set` from its files
+///
+/// License files next to the fonts take precedence over the name table,
+/// whose description is often only a pointer to the full text.
+pub fn collect(set: FontSet) -> LicenseEntry {
+    let info: Option<FontInfo> = set.files.iter().find_map(|path| inspect::inspect(path).ok());
+    let family = set.family.clone()
+        .or_else(|| info.as_ref().and_then(|i| i.family.clone()))
+        .or_else(|| set.font_id.clone())
+        .unwrap_or_else(|| set.files.first().and_then(|f| f.parent()).map(dir_name).unwrap_or_default());
+
+    let mut entry = LicenseEntry {
+        family,
+        provider: set.provider,
+        font_id: set.font_id,
+        license_id: "NOASSERTION".to_string(),
+        source_url: set.source_url,
+        ..Default::default()
+    };
+    if let Some(info) = &info {
+        entry.copyright = info.copyright.clone();
+        entry.designer = info.designer.clone().or_else(|| info.manufacturer.clone());
+        entry.license_url = info.license_url.clone();
+    }
+
+    if let Some((path, text)) = license_file(&set.files) {
+        entry.license_text_from = Some(path.display().to_string());
+        if let Some(license) = FontLicense::from_text(&text) {
+            entry.set_license(license, LicenseSource::LicenseFile);
+        }
+        entry.license_text = Some(text);
+    }
+    if let Some(info) = &info {
+        if entry.license.is_none() {
+            if let Some(license) = info.license_kind() {
+                entry.set_license(license, LicenseSource::NameTable);
+            }
+        }
+        if entry.license_text.is_none() {
+            if let Some(text) = &info.license {
+                entry.license_text = Some(text.clone());
+                entry.license_text_from = Some("name table".to_string());
+            }
+        }
+    }
+    if entry.license_url.is_none() {
+        entry.license_url = entry.license.as_ref().and_then(FontLicense::reference_url);
+    }
+    entry.files = set.files;
+    entry
+}
+
+/// The first license file in the directories holding `files`
+fn license_file(files: &[PathBuf]) -> Option<(PathBuf, String)> {
+    let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+    dirs.dedup();
+    dirs.into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| {
+            let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+            paths.sort();
+            paths
+        })
+        .filter(|path| archive::is_license_file(path))
+        .find_map(|path| std::fs::read_to_string(&path).ok().map(|text| (path, text)))
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// `FONT-LICENSES.md`: a section per family with its full license text
+pub fn markdown(entries: &[LicenseEntry]) -> String {
+    let mut out = String::from("# Font licenses\n\nThis software bundles the following fonts.\n");
+    for entry in entries {
+        out.push_str(&format!("\n## {}\n\n", entry.family));
+        let license = match &entry.license {
+            Some(license) => format!("{} (`{}`)", license.label(), entry.license_id),
+            None => "Unknown".to_string(),
+        };
+        let fields = [
+            ("License", Some(license)),
+            ("Copyright", entry.copyright.clone()),
+            ("Designer", entry.designer.clone()),
+            ("Source", entry.source_url.clone()),
+            ("License URL", entry.license_url.clone()),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value {
+                out.push_str(&format!("- **{}:** {}\n", label, value));
+            }
+        }
+        match &entry.license_text {
+            Some(text) => out.push_str(&format!("\n```text\n{}\n```\n", text.trim_end())),
+            None => out.push_str("\n_No license text was found._\n"),
+        }
+    }
+    out
+}
+
+/// An SPDX 2.3 tag-value document with a package per family
+///
+/// `created` is the document's UTC creation time (see [`utc_timestamp`]).
+/// Licenses SPDX does not list are declared as `LicenseRef-` ids with their
+/// text extracted at the end of the document.
+pub fn spdx(entries: &[LicenseEntry], name: &str, created: &str) -> String {
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "NOASSERTION".to_string());
+    let mut out = format!(
+        "SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\nSPDXID: SPDXRef-DOCUMENT\nDocumentName: {}\n\
+         DocumentNamespace: https://spdx.org/spdxdocs/{}-{}\nCreator: Tool: dx-font-{}\nCreated: {}\n",
+        name,
+        spdx_ident(name),
+        spdx_ident(created),
+        env!("CARGO_PKG_VERSION"),
+        created,
+    );
+
+    let mut extracted: BTreeMap<&str, &LicenseEntry> = BTreeMap::new();
+    for entry in entries {
+        let id = format!("SPDXRef-Package-{}", spdx_ident(&entry.family));
+        out.push_str(&format!("\nPackageName: {}\nSPDXID: {}\n", entry.family, id));
+        out.push_str(&format!("PackageDownloadLocation: {}\n", value(&entry.source_url)));
+        out.push_str("FilesAnalyzed: false\n");
+        out.push_str(&format!("PackageLicenseDeclared: {}\n", entry.license_id));
+        out.push_str(&format!("PackageLicenseConcluded: {}\n", entry.license_id));
+        match &entry.copyright {
+            Some(copyright) => out.push_str(&format!("PackageCopyrightText: <text>{}</text>\n", copyright)),
+            None => out.push_str("PackageCopyrightText: NOASSERTION\n"),
+        }
+        if let Some(designer) = &entry.designer {
+            out.push_str(&format!("PackageOriginator: Person: {}\n", designer));
+        }
+        out.push_str(&format!("Relationship: SPDXRef-DOCUMENT DESCRIBES {}\n", id));
+        if entry.license_id.starts_with("LicenseRef-") {
+            extracted.entry(&entry.license_id).or_insert(entry);
+        }
+    }
+
+    for (id, entry) in extracted {
+        let name = entry.license.as_ref().map(FontLicense::label).unwrap_or_default();
+        let text = entry.license_text.as_deref().unwrap_or(name);
+        out.push_str(&format!("\nLicenseID: {}\nExtractedText: <text>{}</text>\nLicenseName: {}\n", id, text, name));
+    }
+    out
+}
+
+/// `2024-03-09T14:05:00Z` for a Unix timestamp
+pub fn utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+    // Days to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_and_render() {
+        let dir = std::env::temp_dir().join(format!("dx-font-licenses-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("OFL.txt"), "Copyright 2020 The Example Authors\n\nThis Font Software is licensed under the SIL Open Font License, Version 1.1.\n").unwrap();
+        std::fs::write(dir.join("Example-Regular.ttf"), b"not a real font").unwrap();
+
+        let mut entry = collect(FontSet {
+            family: Some("Example Sans".to_string()),
+            files: vec![dir.join("Example-Regular.ttf")],
+            ..Default::default()
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(entry.license, Some(FontLicense::OFL));
+        assert_eq!(entry.license_id, "OFL-1.1");
+        assert!(entry.license_text_from.as_deref().unwrap().ends_with("OFL.txt"));
+
+        entry.copyright = Some("Copyright 2020 The Example Authors".to_string());
+        let md = markdown(std::slice::from_ref(&entry));
+        assert!(md.contains("## Example Sans"));
+        assert!(md.contains("- **License:** OFL (`OFL-1.1`)"));
+        assert!(md.contains("SIL Open Font License, Version 1.1."));
+
+        let doc = spdx(&[entry], "app", "2024-03-09T14:05:00Z");
+        assert!(doc.contains("SPDXID: SPDXRef-Package-Example-Sans"));
+        assert!(doc.contains("PackageLicenseDeclared: OFL-1.1"));
+        assert!(!doc.contains("LicenseID:"));
+        assert_eq!(utc_timestamp(1_709_993_100), "2024-03-09T14:05:00Z");
+    }
+}
@@ -5,12 +5,29 @@
 use anyhow::Result;
 use clap::Parser;
 use console::style;
-use std::path::PathBuf;
+use futures::StreamExt;
+use std::path::Path;
 
-use dx_font::cli::{Cli, Commands, OutputFormat};
+use dx_font::cache::{CachePolicy, CatalogCache};
+use dx_font::cli::{CacheAction, Cli, Commands, ConfigAction, CssArgs, LicenseReport, OutputFormat};
+use dx_font::css;
+use dx_font::config::{self, Config, LoadedConfig};
+use dx_font::providers::ProviderRegistry;
 use dx_font::search::FontSearch;
-use dx_font::download::FontDownloader;
-use dx_font::models::FontProvider;
+use dx_font::batch::{self, DownloadRequest};
+use dx_font::download::{DownloadResult, FontDownloader};
+use dx_font::inspect;
+use dx_font::install::FontInstaller;
+use dx_font::licenses::{self, LicenseEntry};
+use dx_font::manifest::{self, Lockfile, Manifest};
+use dx_font::stream::BatchStatus;
+use dx_font::models::{
+    CanonicalFamily, DownloadOptions, Filter, FontCategory, FontLicense, FontProvider, FontStyle, FontWeight,
+    LicenseTerm, ProviderOutcome, SearchQuery, SearchResults,
+};
+
+/// How long to let background catalog refreshes finish before exiting
+const REFRESH_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -23,185 +40,464 @@ async fn main() -> Result<()> {
         .init();
     
     let cli = Cli::parse();
+    let loaded = cli.load_config()?;
+    let config = &loaded.config;
+    let search = FontSearch::with_config(config, cli.cache_policy())?;
     
-    match cli.command {
-        Commands::Search { query, limit, provider, category } => {
-            cmd_search(&query, limit, provider, category, &cli.format).await?;
+    let outcomes = match cli.command {
+        Commands::Search { query, limit, provider, category, license, all_sources, stream, paging } => {
+            let mut query = build_query(&query, &provider, &category, &license)?;
+            if stream {
+                cmd_search_stream(&search, &query, limit, &cli.format).await?
+            } else {
+                query.dedupe = !all_sources;
+                paging.apply(&mut query, Some(limit))?;
+                cmd_search(&search, query, &cli.format).await?
+            }
+        }
+        Commands::Download { font_id, provider, from, report, output, formats, subsets, weights, italic, variable, force, css } => {
+            let options = DownloadOptions {
+                output_dir: output.unwrap_or_else(|| config.output_dir.clone()),
+                formats: if formats.is_empty() { config.preferred_formats.clone() } else { formats },
+                weights: (!weights.is_empty())
+                    .then(|| weights.into_iter().map(FontWeight::from_numeric).collect()),
+                styles: italic.then(|| vec![FontStyle::Italic]),
+                subsets,
+                force,
+                prefer_variable: variable,
+            };
+            let defaults = DownloadRequest {
+                provider: provider.parse().map_err(anyhow::Error::msg)?,
+                font_id: font_id.unwrap_or_default(),
+                options,
+            };
+            let requests = match &from {
+                Some(path) => batch::read_list(path, &defaults)?,
+                None => vec![defaults],
+            };
+            cmd_download(config, &requests, report.as_deref(), &css, &cli.format).await?;
+            Vec::new()
+        }
+        Commands::Sync { manifest, locked, force, css } => {
+            cmd_sync(config, &manifest, locked, force, &css, &cli.format).await?;
+            Vec::new()
+        }
+        Commands::Install { font_id, provider, force } => {
+            cmd_install(config, &font_id, &provider, force).await?;
+            Vec::new()
         }
-        Commands::Download { font_id, provider, output, formats, subsets } => {
-            cmd_download(&font_id, &provider, &output, &formats, &subsets).await?;
+        Commands::Uninstall { font } => {
+            let installer = FontInstaller::with_config(config)?;
+            let removed = installer.uninstall(&font).await?;
+            println!("{}", style(format!("🗑️  Removed {} ({} files)", removed.name, removed.files.len())).green());
+            Vec::new()
         }
-        Commands::List { provider, limit, category } => {
-            cmd_list(&provider, limit, category, &cli.format).await?;
+        Commands::Installed => {
+            cmd_installed(config, &cli.format).await?;
+            Vec::new()
+        }
+        Commands::List { provider, limit, category, license, all_sources, paging } => {
+            let mut query = build_query("", &provider, &category, &license)?;
+            query.dedupe = !all_sources;
+            paging.apply(&mut query, limit)?;
+            cmd_list(&search, query, &cli.format).await?
         }
         Commands::Info { font_id, provider } => {
-            cmd_info(&font_id, &provider, &cli.format).await?;
+            cmd_info(&search, &font_id, &provider, &cli.format).await?;
+            Vec::new()
+        }
+        Commands::Inspect { files } => {
+            cmd_inspect(&files, &cli.format)?;
+            Vec::new()
+        }
+        Commands::Licenses { path, manifest, installed, report, output } => {
+            let source = match (manifest, installed) {
+                (Some(manifest), _) => LicenseInput::Manifest(manifest),
+                (None, true) => LicenseInput::Installed,
+                (None, false) => LicenseInput::Dir(path.unwrap_or_else(|| config.output_dir.clone())),
+            };
+            let output = output.unwrap_or_else(|| report.file_name().into());
+            cmd_licenses(config, &search, source, report, &output).await?;
+            Vec::new()
         }
         Commands::Stats => {
-            cmd_stats(&cli.format).await?;
+            cmd_stats(&search, &cli.format).await?
         }
         Commands::Health => {
-            cmd_health().await?;
+            cmd_health(&search).await?
         }
         Commands::Providers => {
             cmd_providers().await?;
+            Vec::new()
         }
+        Commands::Cache { action } => {
+            cmd_cache(config, action).await?;
+            Vec::new()
+        }
+        Commands::Config { action } => {
+            cmd_config(&loaded, action, &cli.format)?;
+            Vec::new()
+        }
+    };
+    // Stale catalogs served by --stale-while-revalidate are refreshed in the background
+    search.wait_for_refreshes(REFRESH_WAIT).await;
+    
+    let failed = outcomes.iter().filter(|o| !o.is_ok()).count();
+    if cli.strict && failed > 0 {
+        anyhow::bail!("{} of {} providers failed (--strict)", failed, outcomes.len());
     }
     
     Ok(())
 }
 
-async fn cmd_search(
-    query: &str,
-    limit: usize,
-    provider: Option<String>,
-    category: Option<String>,
-    format: &OutputFormat,
-) -> Result<()> {
+async fn cmd_search(search: &FontSearch, search_query: SearchQuery, format: &OutputFormat) -> Result<Vec<ProviderOutcome>> {
     println!("{}", style("🔍 Searching fonts...").cyan().bold());
     
-    let search = FontSearch::new()?;
-    let results = search.search(query).await?;
+    let dedupe = search_query.dedupe;
+    let results = search.search_advanced(search_query).await?;
+    let query = &results.query;
     
-    let fonts: Vec<_> = results.fonts.into_iter().take(limit).collect();
+    if dedupe {
+        let header = format!("Found {} font families matching '{}'", results.total, query);
+        print_families(&results.families, &header, None, format)?;
+        print_page_footer(&results, results.families.len(), format);
+        print_warnings(&results.outcomes);
+        return Ok(results.outcomes);
+    }
+    
+    let fonts = &results.fonts;
     
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&fonts)?);
+            println!("{}", serde_json::to_string_pretty(fonts)?);
         }
         OutputFormat::Table => {
-            println!("\n{}", style(format!("Found {} fonts matching '{}'", fonts.len(), query)).green());
-            println!("{}", "─".repeat(80));
+            println!("\n{}", style(format!("Found {} fonts matching '{}'", results.total, query)).green());
+            println!("{}", "─".repeat(105));
             println!(
-                "{:<30} {:<20} {:<15} {:<10}",
+                "{:<30} {:<20} {:<15} {:<16} {:<10} {:>7}",
                 style("Name").bold(),
                 style("Provider").bold(),
                 style("Category").bold(),
-                style("Variants").bold()
+                style("License").bold(),
+                style("Variants").bold(),
+                style("Score").bold()
             );
-            println!("{}", "─".repeat(80));
+            println!("{}", "─".repeat(105));
             
-            for font in &fonts {
+            for font in fonts {
                 let category = font.category
                     .as_ref()
                     .map(|c| format!("{:?}", c))
                     .unwrap_or_else(|| "-".to_string());
+                let score = font.score
+                    .map(|s| format!("{:.1}", s))
+                    .unwrap_or_else(|| "-".to_string());
                 
                 println!(
-                    "{:<30} {:<20} {:<15} {:<10}",
+                    "{:<30} {:<20} {:<15} {:<16} {:<10} {:>7}",
                     truncate(&font.name, 28),
                     font.provider.name(),
                     category,
-                    font.variant_count
+                    license_cell(font.license.as_ref(), font.commercial_use_confirmed()),
+                    font.variant_count,
+                    score
                 );
             }
-            println!("{}", "─".repeat(80));
+            println!("{}", "─".repeat(105));
+            print_license_note(fonts.iter().any(|f| !f.commercial_use_confirmed()));
         }
         OutputFormat::Simple => {
-            for font in &fonts {
+            for font in fonts {
                 println!("{} ({})", font.name, font.provider.name());
             }
         }
     }
+    print_page_footer(&results, fonts.len(), format);
+    print_warnings(&results.outcomes);
     
-    Ok(())
+    Ok(results.outcomes)
+}
+
+async fn cmd_search_stream(
+    search: &FontSearch,
+    query: &SearchQuery,
+    limit: usize,
+    format: &OutputFormat,
+) -> Result<Vec<ProviderOutcome>> {
+    if !matches!(format, OutputFormat::Json) {
+        println!("{}", style("🔍 Searching fonts (streaming)...").cyan().bold());
+    }
+    
+    let mut batches = search.search_stream(query);
+    let mut total = 0;
+    let mut outcomes = Vec::new();
+    while let Some(mut batch) = batches.next().await {
+        total += batch.fonts.len();
+        outcomes.push(batch.outcome());
+        batch.fonts.truncate(limit);
+        
+        match format {
+            // One JSON object per provider, printed as it arrives
+            OutputFormat::Json => println!("{}", serde_json::to_string(&batch)?),
+            OutputFormat::Table => {
+                let elapsed = format!("{}ms", batch.elapsed.as_millis());
+                let status = match &batch.status {
+                    BatchStatus::Ok => style(format!("✅ {} matches", batch.fonts.len())).green(),
+                    BatchStatus::Error(e) => style(format!("❌ {}", e)).red(),
+                    BatchStatus::Timeout => style("⏱  timed out".to_string()).yellow(),
+                };
+                println!("\n{} {} {}", style(&batch.provider).bold(), status, style(elapsed).dim());
+                for font in &batch.fonts {
+                    let category = font.category
+                        .as_ref()
+                        .map(|c| format!("{:?}", c))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!("  {:<30} {:<15} {:<10}", truncate(&font.name, 28), category, font.variant_count);
+                }
+            }
+            OutputFormat::Simple => {
+                for font in &batch.fonts {
+                    println!("{} ({})", font.name, font.provider.name());
+                }
+            }
+        }
+    }
+    
+    if matches!(format, OutputFormat::Table) {
+        println!("\n{}", style(format!("{} matches in total", total)).green());
+    }
+    Ok(outcomes)
 }
 
 async fn cmd_download(
-    font_id: &str,
-    provider: &str,
-    output: &PathBuf,
-    formats: &[String],
-    subsets: &[String],
+    config: &Config,
+    requests: &[DownloadRequest],
+    report: Option<&Path>,
+    css: &CssArgs,
+    format: &OutputFormat,
 ) -> Result<()> {
-    println!("{}", style(format!("📥 Downloading font: {}", font_id)).cyan().bold());
+    if let [request] = requests {
+        println!("{}", style(format!("📥 Downloading font: {}", request.font_id)).cyan().bold());
+    } else {
+        println!("{}", style(format!("📥 Downloading {} fonts...", requests.len())).cyan().bold());
+    }
     
-    let downloader = FontDownloader::new()?;
+    let downloader = FontDownloader::with_config(config)?;
+    let results = downloader.download_many(requests).await;
     
-    let formats_ref: Vec<&str> = formats.iter().map(|s| s.as_str()).collect();
-    let subsets_ref: Vec<&str> = subsets.iter().map(|s| s.as_str()).collect();
+    if let Some(path) = report {
+        std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+    }
+    write_stylesheet(config, css, requests, &results)?;
     
-    match provider.to_lowercase().as_str() {
-        "google" | "google-fonts" | "googlefonts" => {
-            let path = downloader.download_google_font(
-                font_id,
-                output,
-                &formats_ref,
-                &subsets_ref,
-            ).await?;
-            
-            println!("{}", style(format!("✅ Downloaded to: {}", path.display())).green());
-        }
-        "fontsource" => {
-            // Download default regular weight
-            let path = downloader.download_fontsource_font(
-                font_id,
-                output,
-                400,
-                "normal",
-            ).await?;
-            
-            println!("{}", style(format!("✅ Downloaded to: {}", path.display())).green());
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        _ if results.len() == 1 => print_download(&results[0]),
+        _ => print_download_summary(&results),
+    }
+    
+    let failed: Vec<&DownloadResult> = results.iter().filter(|r| !r.success).collect();
+    match failed.as_slice() {
+        [] => Ok(()),
+        [only] if results.len() == 1 => anyhow::bail!("{}", only.error.as_deref().unwrap_or("download failed")),
+        _ => anyhow::bail!("{} of {} downloads failed", failed.len(), results.len()),
+    }
+}
+
+async fn cmd_sync(
+    config: &Config,
+    manifest_path: &Path,
+    locked: bool,
+    force: bool,
+    css: &CssArgs,
+    format: &OutputFormat,
+) -> Result<()> {
+    let manifest = Manifest::load(manifest_path)?;
+    let defaults = DownloadOptions {
+        output_dir: config.output_dir.clone(),
+        formats: config.preferred_formats.clone(),
+        force,
+        ..Default::default()
+    };
+    let requests = manifest.requests(manifest_path, &defaults)?;
+    let lock_path = manifest::lock_path(manifest_path);
+    // Read before downloading so a missing lockfile fails fast
+    let expected = if locked { Some(Lockfile::load(&lock_path)?) } else { None };
+    
+    println!("{}", style(format!("🔄 Syncing {} fonts from {}", requests.len(), manifest_path.display())).cyan().bold());
+    let downloader = FontDownloader::with_config(config)?;
+    let results = downloader.download_many(&requests).await;
+    
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        _ => print_download_summary(&results),
+    }
+    
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} fonts failed to sync; {} was not updated", failed, results.len(), lock_path.display());
+    }
+    
+    write_stylesheet(config, css, &requests, &results)?;
+    
+    let lock = Lockfile::from_results(&requests, &results, manifest::manifest_dir(manifest_path));
+    match expected {
+        Some(expected) => {
+            let drift = expected.drift(&lock);
+            if !drift.is_empty() {
+                for line in &drift {
+                    eprintln!("{}", style(format!("  {}", line)).yellow());
+                }
+                anyhow::bail!("{} is out of date ({} differences); run `dx-font sync` without --locked to update it", lock_path.display(), drift.len());
+            }
+            println!("{}", style(format!("✅ Matches {}", lock_path.display())).green());
         }
+        None => {
+            lock.save(&lock_path)?;
+            println!("{}", style(format!("🔒 Wrote {}", lock_path.display())).green());
+        }
+    }
+    Ok(())
+}
+
+/// Write the `--css` stylesheet, if one was asked for
+fn write_stylesheet(config: &Config, args: &CssArgs, requests: &[DownloadRequest], results: &[DownloadResult]) -> Result<()> {
+    let Some(path) = &args.css else {
+        return Ok(());
+    };
+    std::fs::write(path, css::stylesheet(requests, results, &args.apply(&config.css)))?;
+    println!("{}", style(format!("🎨 Wrote {}", path.display())).green());
+    Ok(())
+}
+
+/// Files of a single download and the variants it could not provide
+fn print_download(result: &DownloadResult) {
+    for file in &result.files {
+        if file.skipped {
+            println!("{}", style(format!("⏭️  Already present: {}", file.path.display())).dim());
+        } else {
+            println!("{}", style(format!("✅ Downloaded to: {}", file.path.display())).green());
+        }
+    }
+    if !result.unavailable.is_empty() {
+        let missing: Vec<String> = result.unavailable.iter().map(|v| v.to_string()).collect();
+        eprintln!(
+            "{}",
+            style(format!("⚠️  Not offered by {}: {}", result.provider.name(), missing.join(", "))).yellow()
+        );
+    }
+    if result.success && !result.commercial_use_confirmed() {
+        let license = result.license.as_ref().map_or("unknown license", FontLicense::label);
+        eprintln!(
+            "{}",
+            style(format!(
+                "⚠️  Not confirmed free for commercial use: {} ({})",
+                license,
+                result.license_source.label()
+            )).yellow()
+        );
+    }
+}
+
+/// One row per font of a batch download
+fn print_download_summary(results: &[DownloadResult]) {
+    println!("\n{}", style("Download Summary").green().bold());
+    println!("{}", "─".repeat(90));
+    println!("{:<30} {:<20} {:>6}  Status", "Font", "Provider", "Files");
+    println!("{}", "─".repeat(90));
+    
+    for result in results {
+        let status = match &result.error {
+            Some(e) => style(format!("❌ {}", e)).red(),
+            None if !result.unavailable.is_empty() => {
+                style(format!("⚠️  {} requested variants unavailable", result.unavailable.len())).yellow()
+            }
+            None if result.files.iter().all(|f| f.skipped) => style("⏭️  already present".to_string()).dim(),
+            None => style("✅ OK".to_string()).green(),
+        };
+        println!(
+            "{:<30} {:<20} {:>6}  {}",
+            truncate(&result.font_id, 28),
+            result.provider.name(),
+            result.files.len(),
+            status
+        );
+    }
+    
+    println!("{}", "─".repeat(90));
+    let ok = results.iter().filter(|r| r.success).count();
+    let files: usize = results.iter().map(|r| r.files.len()).sum();
+    println!("{} of {} fonts downloaded, {} files", ok, results.len(), files);
+}
+
+async fn cmd_install(config: &Config, font_id: &str, provider: &str, force: bool) -> Result<()> {
+    let provider: FontProvider = provider.parse().map_err(anyhow::Error::msg)?;
+    let installer = FontInstaller::with_config(config)?;
+    println!("{}", style(format!("📦 Installing {} into {}", font_id, installer.fonts_dir().display())).cyan().bold());
+    
+    let font = installer.install(&provider, font_id, force).await?;
+    for file in &font.files {
+        println!("  {}", file.display());
+    }
+    println!("{}", style(format!("✅ Installed {} ({} files)", font.name, font.files.len())).green());
+    Ok(())
+}
+
+async fn cmd_installed(config: &Config, format: &OutputFormat) -> Result<()> {
+    let fonts = FontInstaller::with_config(config)?.installed().await?;
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&fonts)?),
+        _ if fonts.is_empty() => println!("No fonts installed with dx-font"),
         _ => {
-            let provider_enum = match provider.to_lowercase().as_str() {
-                "bunny" | "bunny-fonts" => FontProvider::BunnyFonts,
-                "fontshare" => FontProvider::FontShare,
-                _ => FontProvider::GoogleFonts,
-            };
-            
-            let paths = downloader.download_font(
-                &provider_enum,
-                font_id,
-                &dx_font::models::DownloadOptions {
-                    output_dir: output.clone(),
-                    formats: formats.to_vec(),
-                    ..Default::default()
-                },
-            ).await?;
-            
-            for path in paths {
-                println!("{}", style(format!("✅ Downloaded to: {}", path.display())).green());
+            println!("{:<30} {:<20} {:<12} {:>6}", "Family", "Provider", "Version", "Files");
+            println!("{}", "─".repeat(72));
+            for font in &fonts {
+                println!(
+                    "{:<30} {:<20} {:<12} {:>6}",
+                    truncate(&font.name, 28),
+                    font.provider.name(),
+                    font.version.as_deref().unwrap_or("-"),
+                    font.files.len()
+                );
             }
         }
     }
-    
     Ok(())
 }
 
-async fn cmd_list(
-    provider: &str,
-    limit: Option<usize>,
-    category: Option<String>,
-    format: &OutputFormat,
-) -> Result<()> {
+async fn cmd_list(search: &FontSearch, query: SearchQuery, format: &OutputFormat) -> Result<Vec<ProviderOutcome>> {
     println!("{}", style("📋 Listing fonts...").cyan().bold());
     
-    let search = FontSearch::new()?;
-    let results = search.list_all().await?;
-    
-    let mut fonts = results.fonts;
+    let dedupe = query.dedupe;
+    let results = search.list_page(query).await?;
     
-    // Apply limit if specified
-    if let Some(l) = limit {
-        fonts.truncate(l);
+    if dedupe {
+        let header = format!("Total: {} font families", results.total);
+        print_families(&results.families, &header, Some(100), format)?;
+        print_page_footer(&results, results.families.len(), format);
+        print_warnings(&results.outcomes);
+        return Ok(results.outcomes);
     }
     
+    let fonts = &results.fonts;
+    
     match format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&fonts)?);
+            println!("{}", serde_json::to_string_pretty(fonts)?);
         }
         OutputFormat::Table => {
-            println!("\n{}", style(format!("Total: {} fonts", fonts.len())).green());
-            println!("{}", "─".repeat(80));
+            println!("\n{}", style(format!("Total: {} fonts", results.total)).green());
+            println!("{}", "─".repeat(97));
             println!(
-                "{:<30} {:<20} {:<15} {:<10}",
+                "{:<30} {:<20} {:<15} {:<16} {:<10}",
                 style("Name").bold(),
                 style("Provider").bold(),
                 style("Category").bold(),
+                style("License").bold(),
                 style("Variants").bold()
             );
-            println!("{}", "─".repeat(80));
+            println!("{}", "─".repeat(97));
             
             for font in fonts.iter().take(100) {
                 let category = font.category
@@ -210,10 +506,11 @@ async fn cmd_list(
                     .unwrap_or_else(|| "-".to_string());
                 
                 println!(
-                    "{:<30} {:<20} {:<15} {:<10}",
+                    "{:<30} {:<20} {:<15} {:<16} {:<10}",
                     truncate(&font.name, 28),
                     font.provider.name(),
                     category,
+                    license_cell(font.license.as_ref(), font.commercial_use_confirmed()),
                     font.variant_count
                 );
             }
@@ -221,30 +518,26 @@ async fn cmd_list(
             if fonts.len() > 100 {
                 println!("... and {} more fonts", fonts.len() - 100);
             }
-            println!("{}", "─".repeat(80));
+            println!("{}", "─".repeat(97));
+            print_license_note(fonts.iter().take(100).any(|f| !f.commercial_use_confirmed()));
         }
         OutputFormat::Simple => {
-            for font in &fonts {
+            for font in fonts {
                 println!("{}", font.name);
             }
         }
     }
+    print_page_footer(&results, fonts.len(), format);
+    print_warnings(&results.outcomes);
     
-    Ok(())
+    Ok(results.outcomes)
 }
 
-async fn cmd_info(font_id: &str, provider: &str, format: &OutputFormat) -> Result<()> {
+async fn cmd_info(search: &FontSearch, font_id: &str, provider: &str, format: &OutputFormat) -> Result<()> {
     println!("{}", style(format!("ℹ️  Getting font info: {}", font_id)).cyan().bold());
     
-    let search = FontSearch::new()?;
     
-    let provider_enum = match provider.to_lowercase().as_str() {
-        "google" | "google-fonts" => FontProvider::GoogleFonts,
-        "bunny" | "bunny-fonts" => FontProvider::BunnyFonts,
-        "fontsource" => FontProvider::Fontsource,
-        "fontshare" => FontProvider::FontShare,
-        _ => FontProvider::GoogleFonts,
-    };
+    let provider_enum: FontProvider = provider.parse().map_err(anyhow::Error::msg)?;
     
     let family = search.get_font_details(&provider_enum, font_id).await?;
     
@@ -264,10 +557,24 @@ async fn cmd_info(font_id: &str, provider: &str, format: &OutputFormat) -> Resul
                 println!("Designer:    {}", designer);
             }
             if let Some(license) = &family.license {
-                println!("License:     {:?}", license);
+                println!(
+                    "License:     {} ({}, {:?})",
+                    license.label(),
+                    family.license_source.label(),
+                    family.license_confidence
+                );
+            }
+            if !family.commercial_use_confirmed() {
+                println!("{}", style("             ⚠️  Not confirmed free for commercial use").yellow());
             }
             println!("Variants:    {}", family.variants.len());
             println!("Subsets:     {}", family.subsets.join(", "));
+            if let Some(date) = &family.date_added {
+                println!("Added:       {}", date);
+            }
+            for axis in family.axes() {
+                println!("Axis:        {} {} to {} (default {})", axis.tag, axis.min, axis.max, axis.default);
+            }
             if let Some(url) = &family.preview_url {
                 println!("Preview:     {}", url);
             }
@@ -282,12 +589,17 @@ async fn cmd_info(font_id: &str, provider: &str, format: &OutputFormat) -> Resul
                     dx_font::FontStyle::Normal => "Normal",
                     dx_font::FontStyle::Italic => "Italic",
                 };
-                println!(
-                    "  {} {} ({})",
-                    variant.weight.to_numeric(),
-                    style_str,
-                    variant.file_format
-                );
+                if variant.is_variable() {
+                    let tags: Vec<&str> = variant.axes.iter().map(|a| a.tag.as_str()).collect();
+                    println!("  Variable [{}] {} ({})", tags.join(","), style_str, variant.file_format);
+                } else {
+                    println!(
+                        "  {} {} ({})",
+                        variant.weight.to_numeric(),
+                        style_str,
+                        variant.file_format
+                    );
+                }
             }
         }
     }
@@ -295,10 +607,115 @@ async fn cmd_info(font_id: &str, provider: &str, format: &OutputFormat) -> Resul
     Ok(())
 }
 
-async fn cmd_stats(format: &OutputFormat) -> Result<()> {
+fn cmd_inspect(files: &[std::path::PathBuf], format: &OutputFormat) -> Result<()> {
+    let infos = files.iter()
+        .map(|path| inspect::inspect(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&infos)?);
+        return Ok(());
+    }
+    
+    for (path, info) in files.iter().zip(&infos) {
+        println!("\n{}", style(path.display()).green().bold());
+        println!("{}", "─".repeat(60));
+        let fields = [
+            ("Family", info.family.clone()),
+            ("Subfamily", info.subfamily.clone()),
+            ("Full name", info.full_name.clone()),
+            ("PostScript name", info.postscript_name.clone()),
+            ("Format", Some(info.format.clone())),
+            ("Weight", Some(info.weight_class.to_string())),
+            ("Italic", Some(if info.italic { "yes" } else { "no" }.to_string())),
+            ("Version", info.version.clone()),
+            ("Designer", info.designer.clone()),
+            ("Manufacturer", info.manufacturer.clone()),
+            ("Copyright", info.copyright.clone()),
+            ("License", info.license.as_deref().map(|l| truncate(l, 60))),
+            ("License URL", info.license_url.clone()),
+            ("Glyphs", Some(info.glyph_count.to_string())),
+            ("Code points", Some(info.codepoint_count.to_string())),
+            ("Subsets", Some(info.subsets.join(", "))),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                println!("  {:<16} {}", label, value);
+            }
+        }
+        for axis in &info.axes {
+            println!(
+                "  {:<16} {} {} ({} to {}, default {})",
+                "Axis",
+                axis.tag,
+                axis.name.as_deref().unwrap_or(""),
+                axis.min,
+                axis.max,
+                axis.default
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Where `licenses` finds its fonts
+enum LicenseInput {
+    Dir(std::path::PathBuf),
+    Manifest(std::path::PathBuf),
+    Installed,
+}
+
+async fn cmd_licenses(
+    config: &Config,
+    search: &FontSearch,
+    input: LicenseInput,
+    report: LicenseReport,
+    output: &Path,
+) -> Result<()> {
+    let (sets, name) = match &input {
+        LicenseInput::Dir(dir) => (licenses::scan_dir(dir)?, dir.display().to_string()),
+        LicenseInput::Manifest(path) => {
+            let lock = Lockfile::load(&manifest::lock_path(path))?;
+            (licenses::from_lockfile(&lock, manifest::manifest_dir(path)), path.display().to_string())
+        }
+        LicenseInput::Installed => {
+            let fonts = FontInstaller::with_config(config)?.installed().await?;
+            (licenses::from_installed(&fonts), "installed fonts".to_string())
+        }
+    };
+    println!("{}", style(format!("📜 Collecting licenses for {} families from {}", sets.len(), name)).cyan().bold());
+    
+    let mut entries: Vec<LicenseEntry> = sets.into_iter().map(licenses::collect).collect();
+    // Directories carry no provider ids; lockfile and installed fonts are looked up
+    for entry in &mut entries {
+        let (Some(provider), Some(font_id)) = (entry.provider.clone(), entry.font_id.clone()) else {
+            continue;
+        };
+        match search.get_font_details(&provider, &font_id).await {
+            Ok(family) => entry.apply_family(&family),
+            Err(e) => eprintln!("{}", style(format!("  {}: no provider details ({})", entry.family, e)).yellow()),
+        }
+    }
+    
+    let text = match report {
+        LicenseReport::Md => licenses::markdown(&entries),
+        LicenseReport::Json => serde_json::to_string_pretty(&entries)?,
+        LicenseReport::Spdx => {
+            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+            licenses::spdx(&entries, &name, &licenses::utc_timestamp(now))
+        }
+    };
+    std::fs::write(output, text)?;
+    
+    for entry in entries.iter().filter(|e| e.license_text.is_none()) {
+        eprintln!("{}", style(format!("  {}: no license text found", entry.family)).yellow());
+    }
+    println!("{}", style(format!("✅ Wrote {} ({} families)", output.display(), entries.len())).green());
+    Ok(())
+}
+
+async fn cmd_stats(search: &FontSearch, format: &OutputFormat) -> Result<Vec<ProviderOutcome>> {
     println!("{}", style("📊 Gathering font statistics...").cyan().bold());
     
-    let search = FontSearch::new()?;
     let stats = search.get_stats().await?;
     
     match format {
@@ -308,10 +725,11 @@ async fn cmd_stats(format: &OutputFormat) -> Result<()> {
         _ => {
             println!("\n{}", style("dx-font Statistics").green().bold());
             println!("{}", "═".repeat(50));
-            println!("Total Fonts:      {}", style(stats.total_fonts).cyan().bold());
+            println!("Unique Families:  {}", style(stats.unique_families).cyan().bold());
+            println!("Total Fonts:      {} (across providers)", stats.total_fonts);
             println!("Providers:        {}", stats.providers_count);
             println!("{}", "─".repeat(50));
-            println!("{}", style("By Category:").bold());
+            println!("{}", style("Families by Category:").bold());
             println!("  Serif:          {}", stats.serif_count);
             println!("  Sans-Serif:     {}", stats.sans_serif_count);
             println!("  Display:        {}", stats.display_count);
@@ -321,31 +739,30 @@ async fn cmd_stats(format: &OutputFormat) -> Result<()> {
             println!("{}", "═".repeat(50));
         }
     }
+    print_warnings(&stats.outcomes);
     
-    Ok(())
+    Ok(stats.outcomes)
 }
 
-async fn cmd_health() -> Result<()> {
+async fn cmd_health(search: &FontSearch) -> Result<Vec<ProviderOutcome>> {
     println!("{}", style("🏥 Checking provider health...").cyan().bold());
     
-    let search = FontSearch::new()?;
-    let health = search.health_check().await;
+    let health = search.health_report().await;
     
     println!("\n{}", style("Provider Health Status").green().bold());
-    println!("{}", "─".repeat(40));
+    println!("{}", "─".repeat(70));
     
-    for (provider, is_healthy) in health {
-        let status = if is_healthy {
-            style("✅ OK").green()
-        } else {
-            style("❌ Error").red()
+    for outcome in &health {
+        let status = match &outcome.error {
+            None => style("✅ OK".to_string()).green(),
+            Some(e) => style(format!("❌ {}", e)).red(),
         };
-        println!("{:<25} {}", provider, status);
+        println!("{:<25} {:>7}ms  {}", outcome.provider, outcome.elapsed_ms, status);
     }
     
-    println!("{}", "─".repeat(40));
+    println!("{}", "─".repeat(70));
     
-    Ok(())
+    Ok(health)
 }
 
 async fn cmd_providers() -> Result<()> {
@@ -379,11 +796,246 @@ async fn cmd_providers() -> Result<()> {
     Ok(())
 }
 
+async fn cmd_cache(config: &Config, action: CacheAction) -> Result<()> {
+    let cache = CatalogCache::from_config(config);
+    
+    match action {
+        CacheAction::Status => {
+            println!("\n{}", style("Catalog Cache").green().bold());
+            println!("Directory: {}", cache.dir().display());
+            println!("{}", "─".repeat(60));
+            
+            let registry = ProviderRegistry::with_config(config)?;
+            for provider in registry.providers() {
+                if provider.is_local() {
+                    println!("{:<25} {}", provider.name(), style("local, not cached").dim());
+                    continue;
+                }
+                match cache.load(provider.name()).await {
+                    Some(catalog) => {
+                        let age = catalog.age();
+                        let state = if catalog.is_fresh(cache.ttl()) {
+                            style("fresh").green()
+                        } else {
+                            style("stale").yellow()
+                        };
+                        println!(
+                            "{:<25} {:>6} fonts  {:>6} min old  {}",
+                            provider.name(),
+                            catalog.fonts.len(),
+                            age.as_secs() / 60,
+                            state
+                        );
+                    }
+                    None => println!("{:<25} {}", provider.name(), style("not cached").red()),
+                }
+            }
+            println!("{}", "─".repeat(60));
+        }
+        CacheAction::Update => {
+            println!("{}", style("🔄 Updating offline catalog snapshot...").cyan().bold());
+            
+            let search = FontSearch::with_config(config, CachePolicy::Refresh)?;
+            let results = search.list_all().await?;
+            
+            println!(
+                "{}",
+                style(format!(
+                    "✅ Cached {} fonts from {} providers in {}",
+                    results.total,
+                    results.providers_searched.len(),
+                    cache.dir().display()
+                )).green()
+            );
+        }
+        CacheAction::Clear => {
+            cache.clear().await?;
+            println!("{}", style(format!("🗑️  Cleared {}", cache.dir().display())).green());
+        }
+    }
+    
+    Ok(())
+}
+
+fn cmd_config(loaded: &LoadedConfig, action: ConfigAction, format: &OutputFormat) -> Result<()> {
+    match action {
+        ConfigAction::Show => {
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&loaded.config)?);
+                }
+                _ => {
+                    if loaded.files.is_empty() {
+                        println!("# Using built-in defaults");
+                    }
+                    for file in &loaded.files {
+                        println!("# Loaded from {}", file.display());
+                    }
+                    print!("{}", toml::to_string_pretty(&loaded.config)?);
+                }
+            }
+        }
+        ConfigAction::Get { key } => {
+            match loaded.config.get(&key)? {
+                serde_json::Value::String(s) => println!("{}", s),
+                value => println!("{}", value),
+            }
+        }
+        ConfigAction::Set { key, value, project } => {
+            let path = if project {
+                // Edit the project file in use, creating the TOML one only if there is none
+                let cwd = std::env::current_dir()?;
+                Config::project_config_path(&cwd)
+                    .unwrap_or_else(|| cwd.join(config::PROJECT_CONFIG_FILES[0]))
+            } else {
+                Config::user_config_path()
+                    .ok_or_else(|| anyhow::anyhow!("Could not determine the user config directory"))?
+            };
+            config::set_in_file(&path, &key, &value)?;
+            println!("{}", style(format!("✅ Set {} in {}", key, path.display())).green());
+        }
+        ConfigAction::Path => {
+            let cwd = std::env::current_dir()?;
+            if let Some(path) = Config::user_config_path() {
+                println!("User:    {}{}", path.display(), if path.is_file() { "" } else { " (not found)" });
+            }
+            match Config::project_config_path(&cwd) {
+                Some(path) => println!("Project: {}", path.display()),
+                None => println!("Project: {} (not found)", cwd.join(config::PROJECT_CONFIG_FILES[0]).display()),
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Print merged families, one row per family with every provider offering it
+fn print_families(
+    families: &[CanonicalFamily],
+    header: &str,
+    max_rows: Option<usize>,
+    format: &OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(families)?);
+        }
+        OutputFormat::Table => {
+            println!("\n{}", style(header).green());
+            println!("{}", "─".repeat(105));
+            println!(
+                "{:<30} {:<28} {:<15} {:<16} {:<10}",
+                style("Name").bold(),
+                style("Providers").bold(),
+                style("Category").bold(),
+                style("License").bold(),
+                style("Variants").bold()
+            );
+            println!("{}", "─".repeat(105));
+            
+            let shown = max_rows.unwrap_or(families.len());
+            for family in families.iter().take(shown) {
+                let category = family.category
+                    .as_ref()
+                    .map(|c| format!("{:?}", c))
+                    .unwrap_or_else(|| "-".to_string());
+                let providers = match family.sources.len() {
+                    1 => family.preferred.name().to_string(),
+                    n => format!("{} +{}", family.preferred.name(), n - 1),
+                };
+                
+                println!(
+                    "{:<30} {:<28} {:<15} {:<16} {:<10}",
+                    truncate(&family.name, 28),
+                    truncate(&providers, 26),
+                    category,
+                    license_cell(family.license.as_ref(), family.commercial_use_confirmed()),
+                    family.preferred_source().variant_count
+                );
+            }
+            
+            if families.len() > shown {
+                println!("... and {} more families", families.len() - shown);
+            }
+            println!("{}", "─".repeat(105));
+            print_license_note(families.iter().take(shown).any(|f| !f.commercial_use_confirmed()));
+        }
+        OutputFormat::Simple => {
+            for family in families {
+                println!("{} ({})", family.name, family.provider_names().join(", "));
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Warn about providers that failed, so "no matches" isn't mistaken for "provider down"
+///
+/// Goes to stderr so JSON output stays parseable.
+fn print_warnings(outcomes: &[ProviderOutcome]) {
+    let failed: Vec<&ProviderOutcome> = outcomes.iter().filter(|o| !o.is_ok()).collect();
+    if failed.is_empty() {
+        return;
+    }
+    
+    eprintln!(
+        "\n{}",
+        style(format!("⚠️  {} of {} providers failed; results may be incomplete:", failed.len(), outcomes.len())).yellow()
+    );
+    for outcome in failed {
+        if let Some(error) = &outcome.error {
+            eprintln!("  {:<20} [{}] {} ({}ms)", outcome.provider, error.kind(), error, outcome.elapsed_ms);
+        }
+    }
+}
+
+/// Tell table readers which rows they are seeing and how to get the next page
+fn print_page_footer(results: &SearchResults, shown: usize, format: &OutputFormat) {
+    if !matches!(format, OutputFormat::Table) || shown == results.total {
+        return;
+    }
+    let first = if shown == 0 { 0 } else { results.offset + 1 };
+    println!("Rows {}-{} of {}", first, results.offset + shown, results.total);
+    if let Some(cursor) = &results.next_cursor {
+        println!("Next page: --cursor {}", cursor);
+    }
+}
+
+/// Build a search query from the CLI's provider, category and license filter values
+fn build_query(query: &str, provider: &[String], category: &[String], license: &[String]) -> Result<SearchQuery> {
+    let provider: Vec<&String> = provider.iter().filter(|p| p.as_str() != "all").collect();
+    let providers = Filter::<FontProvider>::parse(&provider).map_err(anyhow::Error::msg)?;
+    let categories = Filter::<FontCategory>::parse(category).map_err(anyhow::Error::msg)?;
+    let licenses = Filter::<LicenseTerm>::parse(license).map_err(anyhow::Error::msg)?;
+    
+    Ok(SearchQuery {
+        query: query.to_string(),
+        ..Default::default()
+    }
+    .with_provider_filter(providers)
+    .with_category_filter(categories)
+    .with_license_filter(licenses))
+}
+
+/// License column text; `*` marks licenses not confirmed free for commercial use
+fn license_cell(license: Option<&FontLicense>, confirmed: bool) -> String {
+    let label = truncate(license.map_or("-", FontLicense::label), 14);
+    if confirmed { label } else { format!("{}*", label) }
+}
+
+fn print_license_note(flagged: bool) {
+    if flagged {
+        println!("{}", style("* not confirmed free for commercial use").dim());
+    }
+}
+
+/// `s` cut to `max_len` characters, ending in `...` when shortened
 fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{}...", s.chars().take(max_len.saturating_sub(3)).collect::<String>())
     }
 }
 
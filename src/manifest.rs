@@ -0,0 +1,381 @@
+//! Project font manifest and lockfile
+//!
+//! A project declares its fonts in `dx-font.toml`:
+//!
+//! ```toml
+//! output_dir = "public/fonts"
+//!
+//! [fonts.inter]
+//! provider = "google"
+//! weights = [400, 700]
+//! styles = ["normal", "italic"]
+//! formats = ["woff2"]
+//! variable = true
+//!
+//! [fonts.fira-code]
+//! provider = "fontsource"
+//! subsets = ["latin", "cyrillic"]
+//! ```
+//!
+//! `dx-font sync` downloads them and records the result in `dx-font.lock`:
+//! the exact URLs, the provider's `last_modified` date and a SHA-256 per file.
+//! `dx-font sync --locked` compares a fresh resolution against that lockfile
+//! and fails on any difference.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::batch::DownloadRequest;
+use crate::download::DownloadResult;
+use crate::error::{Error, Result};
+use crate::models::{DownloadOptions, FontProvider, FontStyle, FontWeight};
+
+/// Default manifest file name
+pub const MANIFEST_FILE: &str = "dx-font.toml";
+
+/// Lockfile format version
+const LOCK_VERSION: u32 = 1;
+
+/// The project manifest (`dx-font.toml`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Where fonts are downloaded, relative to the manifest's directory;
+    /// defaults to `output_dir` from the config
+    pub output_dir: Option<PathBuf>,
+
+    /// Families keyed by font id
+    #[serde(default)]
+    pub fonts: BTreeMap<String, FontSpec>,
+}
+
+/// One family in the manifest; unset filters mean "everything offered"
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FontSpec {
+    /// Provider name or alias (`google`, `fontsource`, ...); Google Fonts by default
+    pub provider: Option<String>,
+    /// Font id at the provider when it differs from the table key
+    pub id: Option<String>,
+    pub weights: Option<Vec<u16>>,
+    /// `normal` and/or `italic`
+    pub styles: Option<Vec<String>>,
+    pub subsets: Option<Vec<String>>,
+    /// Defaults to `preferred_formats` from the config
+    pub formats: Option<Vec<String>>,
+    /// Prefer the variable font over static instances
+    pub variable: bool,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read(path, "not found")?;
+        toml::from_str(&text).map_err(|e| invalid(path, e.message()))
+    }
+
+    /// Download requests for every family, in id order
+    ///
+    /// `defaults` supplies the output directory and formats that the manifest
+    /// leaves out. A relative `output_dir` is relative to the manifest's directory.
+    pub fn requests(&self, path: &Path, defaults: &DownloadOptions) -> Result<Vec<DownloadRequest>> {
+        self.fonts
+            .iter()
+            .map(|(key, spec)| {
+                let provider = match &spec.provider {
+                    Some(name) => name.parse().map_err(|e: String| invalid(path, &e))?,
+                    None => FontProvider::GoogleFonts,
+                };
+                let styles = match &spec.styles {
+                    Some(styles) => Some(
+                        styles.iter()
+                            .map(|s| parse_style(s).ok_or_else(|| invalid(path, &format!("unknown style '{}' for {}", s, key))))
+                            .collect::<Result<Vec<_>>>()?,
+                    ),
+                    None => None,
+                };
+
+                let mut options = defaults.clone();
+                if let Some(dir) = &self.output_dir {
+                    options.output_dir = manifest_dir(path).join(dir);
+                }
+                if let Some(formats) = &spec.formats {
+                    options.formats = formats.clone();
+                }
+                if let Some(subsets) = &spec.subsets {
+                    options.subsets = subsets.clone();
+                }
+                options.weights = spec.weights.as_ref()
+                    .map(|w| w.iter().copied().map(FontWeight::from_numeric).collect());
+                options.styles = styles;
+                options.prefer_variable = spec.variable;
+
+                Ok(DownloadRequest {
+                    provider,
+                    font_id: spec.id.clone().unwrap_or_else(|| key.clone()),
+                    options,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The lockfile (`dx-font.lock`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "font")]
+    pub fonts: Vec<LockedFont>,
+}
+
+/// A resolved family
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFont {
+    pub id: String,
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// The request this entry was resolved from, so manifest edits show up as drift
+    pub request: String,
+    #[serde(default, rename = "file")]
+    pub files: Vec<LockedFile>,
+}
+
+/// A downloaded file pinned by URL and content hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    pub path: String,
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl Lockfile {
+    /// Lock the outcome of a sync; `requests` and `results` line up
+    ///
+    /// File paths are stored relative to `root`, the manifest's directory,
+    /// so the lockfile works wherever the project is checked out.
+    pub fn from_results(requests: &[DownloadRequest], results: &[DownloadResult], root: &Path) -> Self {
+        let mut fonts: Vec<LockedFont> = requests.iter()
+            .zip(results)
+            .map(|(request, result)| {
+                let mut files: Vec<LockedFile> = result.files.iter()
+                    .map(|f| LockedFile {
+                        path: relative_path(&f.path, root).to_string_lossy().replace('\\', "/"),
+                        url: f.url.clone(),
+                        sha256: f.sha256.clone(),
+                        size: f.size,
+                    })
+                    .collect();
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+
+                LockedFont {
+                    id: request.font_id.clone(),
+                    provider: request.provider.name().to_string(),
+                    last_modified: result.last_modified.clone(),
+                    request: describe_request(&request.options),
+                    files,
+                }
+            })
+            .collect();
+        fonts.sort_by(|a, b| (&a.id, &a.provider).cmp(&(&b.id, &b.provider)));
+        Self { version: LOCK_VERSION, fonts }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read(path, "not found; run `dx-font sync` to create it")?;
+        let lock: Self = toml::from_str(&text).map_err(|e| invalid(path, e.message()))?;
+        if lock.version != LOCK_VERSION {
+            return Err(invalid(path, &format!("unsupported lockfile version {}", lock.version)));
+        }
+        Ok(lock)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string_pretty(self).map_err(|e| invalid(path, &e.to_string()))?;
+        let header = "# Generated by `dx-font sync`. Do not edit by hand.\n\n";
+        std::fs::write(path, format!("{}{}", header, text))?;
+        Ok(())
+    }
+
+    /// Human readable differences between this (expected) lock and `actual`
+    pub fn drift(&self, actual: &Lockfile) -> Vec<String> {
+        let key = |f: &LockedFont| format!("{} ({})", f.id, f.provider);
+        let expected: BTreeMap<String, &LockedFont> = self.fonts.iter().map(|f| (key(f), f)).collect();
+        let actual: BTreeMap<String, &LockedFont> = actual.fonts.iter().map(|f| (key(f), f)).collect();
+        let mut drift = Vec::new();
+
+        for (name, old) in &expected {
+            let Some(new) = actual.get(name) else {
+                drift.push(format!("{}: in the lockfile but not in the manifest", name));
+                continue;
+            };
+            if old.request != new.request {
+                drift.push(format!("{}: request changed from '{}' to '{}'", name, old.request, new.request));
+            }
+            if old.last_modified != new.last_modified {
+                drift.push(format!(
+                    "{}: provider version changed from {} to {}",
+                    name,
+                    old.last_modified.as_deref().unwrap_or("unknown"),
+                    new.last_modified.as_deref().unwrap_or("unknown"),
+                ));
+            }
+
+            let old_files: BTreeMap<&str, &LockedFile> = old.files.iter().map(|f| (f.path.as_str(), f)).collect();
+            let new_files: BTreeMap<&str, &LockedFile> = new.files.iter().map(|f| (f.path.as_str(), f)).collect();
+            for (path, file) in &old_files {
+                match new_files.get(path) {
+                    None => drift.push(format!("{}: {} is no longer produced", name, path)),
+                    Some(now) if now.url != file.url => drift.push(format!("{}: {} now comes from {}", name, path, now.url)),
+                    Some(now) if now.sha256 != file.sha256 => drift.push(format!("{}: {} has a different SHA-256", name, path)),
+                    Some(_) => {}
+                }
+            }
+            for path in new_files.keys().filter(|p| !old_files.contains_key(*p)) {
+                drift.push(format!("{}: {} is new", name, path));
+            }
+        }
+        for name in actual.keys().filter(|n| !expected.contains_key(*n)) {
+            drift.push(format!("{}: in the manifest but not in the lockfile", name));
+        }
+        drift
+    }
+}
+
+/// Directory a manifest's relative paths are resolved against
+pub fn manifest_dir(manifest: &Path) -> &Path {
+    manifest.parent().unwrap_or(Path::new(""))
+}
+
+/// `path` relative to `base`, stepping up with `..` where they part
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let (Ok(path), Ok(base)) = (std::path::absolute(path), std::path::absolute(base)) else {
+        return path.to_path_buf();
+    };
+    let mut path_parts = path.components().peekable();
+    let mut base_parts = base.components().peekable();
+    while let (Some(a), Some(b)) = (path_parts.peek(), base_parts.peek()) {
+        if a != b {
+            break;
+        }
+        path_parts.next();
+        base_parts.next();
+    }
+    base_parts.map(|_| std::path::Component::ParentDir)
+        .chain(path_parts)
+        .collect()
+}
+
+/// Lockfile path next to a manifest (`dx-font.toml` -> `dx-font.lock`)
+pub fn lock_path(manifest: &Path) -> PathBuf {
+    manifest.with_extension("lock")
+}
+
+/// Stable one-line summary of the filters in `options`
+fn describe_request(options: &DownloadOptions) -> String {
+    let list = |items: Vec<String>| if items.is_empty() { "*".to_string() } else { items.join(",") };
+    let weights = options.weights.as_ref()
+        .map(|w| list(w.iter().map(|w| w.to_numeric().to_string()).collect()))
+        .unwrap_or_else(|| "*".to_string());
+    let styles = options.styles.as_ref()
+        .map(|s| list(s.iter().map(|s| style_name(*s).to_string()).collect()))
+        .unwrap_or_else(|| "*".to_string());
+    let variable = if options.prefer_variable { " variable" } else { "" };
+    format!(
+        "weights={} styles={} formats={} subsets={}{}",
+        weights,
+        styles,
+        list(options.formats.clone()),
+        list(options.subsets.clone()),
+        variable,
+    )
+}
+
+fn parse_style(s: &str) -> Option<FontStyle> {
+    match s.to_lowercase().as_str() {
+        "normal" | "regular" | "upright" => Some(FontStyle::Normal),
+        "italic" | "oblique" => Some(FontStyle::Italic),
+        _ => None,
+    }
+}
+
+fn style_name(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Normal => "normal",
+        FontStyle::Italic => "italic",
+    }
+}
+
+fn read(path: &Path, missing: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => invalid(path, missing),
+        _ => e.into(),
+    })
+}
+
+fn invalid(path: &Path, message: &str) -> Error {
+    Error::Manifest {
+        path: path.display().to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::DownloadedFile;
+
+    #[test]
+    fn test_manifest_to_lock_and_drift() {
+        let manifest: Manifest = toml::from_str(r#"
+            output_dir = "public/fonts"
+
+            [fonts.inter]
+            weights = [400, 700]
+            styles = ["italic"]
+            formats = ["woff2"]
+        "#).unwrap();
+        let manifest_path = Path::new("web").join(MANIFEST_FILE);
+        let requests = manifest.requests(&manifest_path, &DownloadOptions::default()).unwrap();
+        assert_eq!(requests[0].provider, FontProvider::GoogleFonts);
+        assert_eq!(requests[0].options.output_dir, PathBuf::from("web/public/fonts"));
+        assert_eq!(requests[0].options.styles, Some(vec![FontStyle::Italic]));
+
+        let result = |sha256: &str| DownloadResult {
+            font_id: "inter".to_string(),
+            provider: FontProvider::GoogleFonts,
+            files: vec![DownloadedFile {
+                path: PathBuf::from("web/public/fonts/Inter/Inter-Italic.woff2"),
+                url: "https://example.com/inter.zip".to_string(),
+                size: 3,
+                sha256: sha256.to_string(),
+                skipped: false,
+            }],
+            success: true,
+            error: None,
+            unavailable: Vec::new(),
+            last_modified: Some("2024-01-01".to_string()),
+            family: Some("Inter".to_string()),
+            category: None,
+            license: None,
+            license_source: Default::default(),
+            license_confidence: Default::default(),
+        };
+        let locked = Lockfile::from_results(&requests, &[result("aaa")], manifest_dir(&manifest_path));
+        assert_eq!(locked.fonts[0].request, "weights=400,700 styles=italic formats=woff2 subsets=latin");
+
+        let text = toml::to_string_pretty(&locked).unwrap();
+        assert_eq!(toml::from_str::<Lockfile>(&text).unwrap(), locked);
+
+        assert!(locked.drift(&locked).is_empty());
+        let changed = Lockfile::from_results(&requests, &[result("bbb")], manifest_dir(&manifest_path));
+        assert_eq!(locked.drift(&changed), ["inter (Google Fonts): public/fonts/Inter/Inter-Italic.woff2 has a different SHA-256"]);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new("/srv/web/fonts/a.woff2"), Path::new("/srv/web")), PathBuf::from("fonts/a.woff2"));
+        assert_eq!(relative_path(Path::new("/srv/fonts/a.woff2"), Path::new("/srv/web")), PathBuf::from("../fonts/a.woff2"));
+    }
+}
@@ -1,12 +1,15 @@
 //! Core data models for dx-font
 
 use serde::{Deserialize, Serialize};
+use crate::error::{ErrorKind, ProviderError};
+use std::str::FromStr;
 
 /// Represents a font provider/source
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FontProvider {
     // Tier 1: Primary APIs
     GoogleFonts,
+    GoogleFontsRepo,
     BunnyFonts,
     Fontsource,
     GoogleWebfontsHelper,
@@ -66,6 +69,7 @@ impl FontProvider {
     pub fn name(&self) -> &str {
         match self {
             FontProvider::GoogleFonts => "Google Fonts",
+            FontProvider::GoogleFontsRepo => "Google Fonts Repository",
             FontProvider::BunnyFonts => "Bunny Fonts",
             FontProvider::Fontsource => "Fontsource",
             FontProvider::GoogleWebfontsHelper => "Google Webfonts Helper",
@@ -115,6 +119,7 @@ impl FontProvider {
     pub fn base_url(&self) -> &str {
         match self {
             FontProvider::GoogleFonts => "https://fonts.google.com",
+            FontProvider::GoogleFontsRepo => "https://github.com/google/fonts",
             FontProvider::BunnyFonts => "https://fonts.bunny.net",
             FontProvider::Fontsource => "https://api.fontsource.org/v1/fonts",
             FontProvider::GoogleWebfontsHelper => "https://gwfh.mranftl.com/api/fonts",
@@ -162,6 +167,71 @@ impl FontProvider {
     }
 }
 
+impl FontProvider {
+    /// Every known (non-custom) provider
+    pub fn known() -> Vec<FontProvider> {
+        vec![
+            FontProvider::GoogleFonts, FontProvider::GoogleFontsRepo, FontProvider::BunnyFonts, FontProvider::Fontsource,
+            FontProvider::GoogleWebfontsHelper, FontProvider::FontLibrary,
+            FontProvider::FontSquirrel, FontProvider::DaFont, FontProvider::Fonts1001,
+            FontProvider::FontSpace, FontProvider::AbstractFonts, FontProvider::UrbanFonts,
+            FontProvider::FontZone, FontProvider::FFonts, FontProvider::FontMeme,
+            FontProvider::FontRiver, FontProvider::FontShare, FontProvider::Velvetyne,
+            FontProvider::OpenFoundry, FontProvider::LeagueOfMoveableType, FontProvider::Uncut,
+            FontProvider::Collletttivo, FontProvider::OmnibusType, FontProvider::FreeFacesGallery,
+            FontProvider::UseModify, FontProvider::BeautifulWebType, FontProvider::Fontain,
+            FontProvider::GoodFonts, FontProvider::Befonts, FontProvider::LostType,
+            FontProvider::AtipoFoundry, FontProvider::GitHub, FontProvider::NotoFonts,
+            FontProvider::ArabicFonts, FontProvider::ChinazFonts, FontProvider::FreeJapaneseFonts,
+            FontProvider::Noonnu, FontProvider::HindiFonts, FontProvider::ThaiFonts,
+            FontProvider::FonterRu, FontProvider::FontsIr, FontProvider::TamilFonts,
+            FontProvider::BengaliFonts, FontProvider::SMCMalayalam,
+        ]
+    }
+}
+
+/// Lowercase and strip everything but letters and digits ("Google-Fonts" -> "googlefonts")
+fn normalize_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// `s` reduced to the characters SPDX allows in ids: `Free for personal use` becomes `Free-for-personal-use`
+pub fn spdx_ident(s: &str) -> String {
+    s.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl FromStr for FontProvider {
+    type Err = String;
+    
+    /// Parse a provider from its display name or a common alias
+    /// (`google`, `bunny`, `squirrel`, `1001`, `gh`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = normalize_key(s);
+        let provider = match key.as_str() {
+            "google" | "gf" | "googlefonts" => FontProvider::GoogleFonts,
+            "gfrepo" | "googlefontsrepo" | "metadata" => FontProvider::GoogleFontsRepo,
+            "bunny" | "bunnyfonts" => FontProvider::BunnyFonts,
+            "gwfh" | "webfontshelper" => FontProvider::GoogleWebfontsHelper,
+            "fontlibrary" | "openfontlibrary" => FontProvider::FontLibrary,
+            "squirrel" | "fontsquirrel" => FontProvider::FontSquirrel,
+            "1001" | "1001fonts" | "fonts1001" => FontProvider::Fonts1001,
+            "github" | "gh" | "githubfonts" => FontProvider::GitHub,
+            "noto" => FontProvider::NotoFonts,
+            _ => FontProvider::known()
+                .into_iter()
+                .find(|p| normalize_key(p.name()) == key || normalize_key(&format!("{:?}", p)) == key)
+                .ok_or_else(|| format!("unknown provider '{}'", s))?,
+        };
+        Ok(provider)
+    }
+}
+
 /// Font weight enumeration
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FontWeight {
@@ -223,8 +293,68 @@ pub enum FontCategory {
     Monospace,
 }
 
+impl FontCategory {
+    pub fn name(&self) -> &str {
+        match self {
+            FontCategory::Serif => "serif",
+            FontCategory::SansSerif => "sans-serif",
+            FontCategory::Display => "display",
+            FontCategory::Handwriting => "handwriting",
+            FontCategory::Monospace => "monospace",
+        }
+    }
+}
+
+impl FromStr for FontCategory {
+    type Err = String;
+    
+    /// Parse a category from its name or a common alias (`sans`, `mono`, `script`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize_key(s).as_str() {
+            "serif" | "slab" | "slabserif" => Ok(FontCategory::Serif),
+            "sans" | "sansserif" => Ok(FontCategory::SansSerif),
+            "display" | "decorative" => Ok(FontCategory::Display),
+            "handwriting" | "handwritten" | "script" | "hand" => Ok(FontCategory::Handwriting),
+            "mono" | "monospace" | "monospaced" | "code" => Ok(FontCategory::Monospace),
+            _ => Err(format!("unknown category '{}'", s)),
+        }
+    }
+}
+
+/// Include/exclude lists parsed from filter values like `google,bunny` or `!dafont`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter<T> {
+    pub include: Vec<T>,
+    pub exclude: Vec<T>,
+}
+
+impl<T> Default for Filter<T> {
+    fn default() -> Self {
+        Self { include: Vec::new(), exclude: Vec::new() }
+    }
+}
+
+impl<T: FromStr<Err = String>> Filter<T> {
+    /// Parse filter values; each may hold several comma-separated entries,
+    /// and entries prefixed with `!` are exclusions
+    pub fn parse<S: AsRef<str>>(values: &[S]) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for entry in values.iter().flat_map(|v| v.as_ref().split(',')) {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            match entry.strip_prefix('!') {
+                Some(excluded) => filter.exclude.push(excluded.trim().parse()?),
+                None => filter.include.push(entry.parse()?),
+            }
+        }
+        Ok(filter)
+    }
+}
+
 /// License type for fonts
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum FontLicense {
     OFL,      // SIL Open Font License
     Apache2,  // Apache License 2.0
@@ -235,13 +365,225 @@ pub enum FontLicense {
     Custom(String),
 }
 
+impl FontLicense {
+    /// Read an SPDX id or license name (`OFL-1.1`, `Apache License, Version 2.0`)
+    ///
+    /// Names that are not recognized are kept as [`FontLicense::Custom`].
+    pub fn from_identifier(s: &str) -> FontLicense {
+        let key = normalize_key(s);
+        if key.contains("openfontlicense") || key.starts_with("ofl") || key.contains("silofl") {
+            FontLicense::OFL
+        } else if key.starts_with("apache") {
+            FontLicense::Apache2
+        } else if key == "mit" || key.starts_with("mitlicense") {
+            FontLicense::MIT
+        } else if key.starts_with("gpl") || key.contains("generalpubliclicense") {
+            FontLicense::GPL
+        } else if key.starts_with("publicdomain") || key.starts_with("cc0") || key == "unlicense" {
+            FontLicense::PublicDomain
+        } else {
+            FontLicense::Custom(s.trim().to_string())
+        }
+    }
+    
+    /// Recognize a license from its text, a description of it or its URL
+    ///
+    /// Unlike [`FontLicense::from_identifier`], unrecognized text gives `None`.
+    pub fn from_text(text: &str) -> Option<FontLicense> {
+        let text = text.to_lowercase();
+        if text.contains("open font license") || text.contains("sil.org/ofl") || text.contains("openfontlicense.org") {
+            Some(FontLicense::OFL)
+        } else if text.contains("apache license") || text.contains("apache.org/licenses") {
+            Some(FontLicense::Apache2)
+        } else if text.contains("mit license") || text.contains("opensource.org/licenses/mit") {
+            Some(FontLicense::MIT)
+        } else if text.contains("gnu general public license") || text.contains("gnu.org/licenses/gpl") {
+            Some(FontLicense::GPL)
+        } else if text.contains("public domain") || text.contains("creativecommons.org/publicdomain") {
+            Some(FontLicense::PublicDomain)
+        } else {
+            None
+        }
+    }
+    
+    /// SPDX license id, or a `LicenseRef-` id for licenses SPDX does not list
+    pub fn spdx_id(&self) -> String {
+        match self {
+            FontLicense::OFL => "OFL-1.1".to_string(),
+            FontLicense::Apache2 => "Apache-2.0".to_string(),
+            FontLicense::MIT => "MIT".to_string(),
+            // The GPL version is not tracked
+            FontLicense::GPL => "LicenseRef-GPL".to_string(),
+            FontLicense::PublicDomain => "LicenseRef-PublicDomain".to_string(),
+            FontLicense::FreeCommercial => "LicenseRef-FreeCommercial".to_string(),
+            FontLicense::Custom(name) => format!("LicenseRef-{}", spdx_ident(name)),
+        }
+    }
+    
+    /// Canonical text of licenses SPDX lists
+    pub fn reference_url(&self) -> Option<String> {
+        let id = self.spdx_id();
+        (!id.starts_with("LicenseRef-")).then(|| format!("https://spdx.org/licenses/{}.html", id))
+    }
+    
+    /// Whether the license itself permits commercial use
+    ///
+    /// Custom licenses are never assumed to.
+    pub fn allows_commercial_use(&self) -> bool {
+        !matches!(self, FontLicense::Custom(_))
+    }
+    
+    /// Short label for tables (`OFL`, `Apache-2.0`, ...)
+    pub fn label(&self) -> &str {
+        match self {
+            FontLicense::OFL => "OFL",
+            FontLicense::Apache2 => "Apache-2.0",
+            FontLicense::MIT => "MIT",
+            FontLicense::GPL => "GPL",
+            FontLicense::PublicDomain => "Public domain",
+            FontLicense::FreeCommercial => "Free commercial",
+            FontLicense::Custom(name) => name,
+        }
+    }
+}
+
+/// Where a font's license information comes from
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseSource {
+    /// A license field in the provider's API response
+    ProviderApi,
+    /// A license label on the provider's web page
+    ScrapedPage,
+    /// The license entry (name ID 13) embedded in the font file
+    NameTable,
+    /// A license file (`OFL.txt`, `LICENSE`) shipped next to the font files
+    LicenseFile,
+    /// The provider's terms for its whole catalog, not the individual font
+    #[default]
+    Catalog,
+}
+
+impl LicenseSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LicenseSource::ProviderApi => "provider API",
+            LicenseSource::ScrapedPage => "scraped page",
+            LicenseSource::NameTable => "name table",
+            LicenseSource::LicenseFile => "license file",
+            LicenseSource::Catalog => "catalog terms",
+        }
+    }
+}
+
+/// How far a font's license information can be trusted, weakest first
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseConfidence {
+    /// Assigned without per-font evidence
+    #[default]
+    Unverified,
+    /// Stated by the provider in a label or in catalog-wide terms
+    Reported,
+    /// Named explicitly by the provider's metadata or the font file
+    Verified,
+}
+
+/// Whether `license`, held with `confidence`, confirms the font is free for commercial use
+pub fn commercial_use_confirmed(license: Option<&FontLicense>, confidence: LicenseConfidence) -> bool {
+    confidence >= LicenseConfidence::Reported && license.is_some_and(FontLicense::allows_commercial_use)
+}
+
+/// One `--license` filter entry: a license, or any license confirmed free for commercial use
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LicenseTerm {
+    License(FontLicense),
+    CommercialOk,
+}
+
+impl LicenseTerm {
+    pub fn matches(&self, font: &Font) -> bool {
+        match self {
+            LicenseTerm::License(license) => font.license.as_ref() == Some(license),
+            LicenseTerm::CommercialOk => font.commercial_use_confirmed(),
+        }
+    }
+}
+
+impl FromStr for LicenseTerm {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let license = match normalize_key(s).as_str() {
+            "commercialok" | "commercial" => return Ok(LicenseTerm::CommercialOk),
+            "ofl" | "sil" => FontLicense::OFL,
+            "apache" | "apache2" | "apache20" => FontLicense::Apache2,
+            "mit" => FontLicense::MIT,
+            "gpl" => FontLicense::GPL,
+            "publicdomain" | "pd" | "cc0" => FontLicense::PublicDomain,
+            "freecommercial" => FontLicense::FreeCommercial,
+            _ => return Err(format!(
+                "unknown license '{}' (expected ofl, apache, mit, gpl, public-domain, free-commercial or commercial-ok)",
+                s
+            )),
+        };
+        Ok(LicenseTerm::License(license))
+    }
+}
+
 /// A single font variant (e.g., Regular, Bold Italic)
+///
+/// A variant with `axes` is a variable font covering every instance in those
+/// ranges; its `weight` is the default instance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontVariant {
     pub weight: FontWeight,
     pub style: FontStyle,
     pub file_url: Option<String>,
     pub file_format: String, // ttf, otf, woff, woff2
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub axes: Vec<FontAxis>,
+}
+
+impl FontVariant {
+    pub fn is_variable(&self) -> bool {
+        !self.axes.is_empty()
+    }
+    
+    /// Whether this variant provides `weight`, as a static instance or within its `wght` range
+    pub fn covers_weight(&self, weight: FontWeight) -> bool {
+        match self.axes.iter().find(|a| a.tag == "wght") {
+            Some(axis) => axis.contains(weight.to_numeric() as f32),
+            None => weight == self.weight,
+        }
+    }
+}
+
+/// A variation axis of a variable font (`wght`, `wdth`, `opsz`, `slnt`, `ital` or a custom tag)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontAxis {
+    pub tag: String,
+    /// Display name, when the source provides one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+}
+
+impl FontAxis {
+    pub fn new(tag: &str, min: f32, default: f32, max: f32) -> Self {
+        Self { tag: tag.to_string(), name: None, min, default, max }
+    }
+    
+    /// Registered axes are lowercase; custom axes are uppercase (`GRAD`, `XOPQ`)
+    pub fn is_registered(&self) -> bool {
+        matches!(self.tag.as_str(), "wght" | "wdth" | "opsz" | "slnt" | "ital")
+    }
+    
+    pub fn contains(&self, value: f32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
 }
 
 /// Represents a font family with all its variants
@@ -253,6 +595,10 @@ pub struct FontFamily {
     pub category: Option<FontCategory>,
     pub variants: Vec<FontVariant>,
     pub license: Option<FontLicense>,
+    #[serde(default)]
+    pub license_source: LicenseSource,
+    #[serde(default)]
+    pub license_confidence: LicenseConfidence,
     pub designer: Option<String>,
     pub description: Option<String>,
     pub preview_url: Option<String>,
@@ -261,6 +607,30 @@ pub struct FontFamily {
     pub subsets: Vec<String>,
     pub popularity: Option<u32>,
     pub last_modified: Option<String>,
+    /// When the family was added to the provider's catalog (`2022-03-07`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_added: Option<String>,
+}
+
+impl FontFamily {
+    pub fn is_variable(&self) -> bool {
+        self.variants.iter().any(FontVariant::is_variable)
+    }
+    
+    pub fn commercial_use_confirmed(&self) -> bool {
+        commercial_use_confirmed(self.license.as_ref(), self.license_confidence)
+    }
+    
+    /// Variation axes across all variable variants, first occurrence of each tag
+    pub fn axes(&self) -> Vec<&FontAxis> {
+        let mut axes: Vec<&FontAxis> = Vec::new();
+        for axis in self.variants.iter().flat_map(|v| &v.axes) {
+            if !axes.iter().any(|a| a.tag == axis.tag) {
+                axes.push(axis);
+            }
+        }
+        axes
+    }
 }
 
 /// A simplified font representation for search results
@@ -272,8 +642,49 @@ pub struct Font {
     pub category: Option<FontCategory>,
     pub variant_count: usize,
     pub license: Option<FontLicense>,
+    #[serde(default)]
+    pub license_source: LicenseSource,
+    #[serde(default)]
+    pub license_confidence: LicenseConfidence,
     pub preview_url: Option<String>,
     pub download_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub designer: Option<String>,
+    /// Popularity rank reported by the provider (1 = most popular)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub popularity: Option<u32>,
+    /// Relevance score assigned by the search ranking stage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+}
+
+impl Font {
+    pub fn commercial_use_confirmed(&self) -> bool {
+        commercial_use_confirmed(self.license.as_ref(), self.license_confidence)
+    }
+}
+
+#[cfg(test)]
+impl Font {
+    /// A bare font for test fixtures, id derived from the name; set the other
+    /// fields with struct-update syntax
+    pub(crate) fn test(name: &str, provider: FontProvider) -> Self {
+        Font {
+            id: name.to_lowercase().replace(' ', "-"),
+            name: name.to_string(),
+            provider,
+            category: None,
+            variant_count: 1,
+            license: None,
+            license_source: Default::default(),
+            license_confidence: Default::default(),
+            preview_url: None,
+            download_url: None,
+            designer: None,
+            popularity: None,
+            score: None,
+        }
+    }
 }
 
 impl From<FontFamily> for Font {
@@ -285,8 +696,13 @@ impl From<FontFamily> for Font {
             category: family.category,
             variant_count: family.variants.len(),
             license: family.license,
+            license_source: family.license_source,
+            license_confidence: family.license_confidence,
             preview_url: family.preview_url,
             download_url: family.download_url,
+            designer: family.designer,
+            popularity: family.popularity,
+            score: None,
         }
     }
 }
@@ -295,20 +711,205 @@ impl From<FontFamily> for Font {
 #[derive(Debug, Clone, Default)]
 pub struct SearchQuery {
     pub query: String,
+    /// Only search these providers (all when `None`)
     pub providers: Option<Vec<FontProvider>>,
+    /// Never search these providers
+    pub exclude_providers: Vec<FontProvider>,
+    /// Only return fonts in this category
     pub category: Option<FontCategory>,
+    /// Only return fonts in one of these categories (combined with `category`)
+    pub categories: Option<Vec<FontCategory>>,
+    /// Never return fonts in these categories
+    pub exclude_categories: Vec<FontCategory>,
     pub license: Option<FontLicense>,
+    /// Only return fonts matching one of these license terms (combined with `license`)
+    pub licenses: Option<Vec<LicenseTerm>>,
+    /// Never return fonts matching these license terms
+    pub exclude_licenses: Vec<LicenseTerm>,
+    /// Page size (all results when `None`)
     pub limit: Option<usize>,
+    /// Rows to skip before the page starts
     pub offset: Option<usize>,
+    /// Cursor from a previous page's `next_cursor` (takes precedence over `offset`)
+    pub cursor: Option<String>,
+    /// Merge rows of the same family from different providers
+    pub dedupe: bool,
+}
+
+impl SearchQuery {
+    /// Apply a parsed provider filter
+    pub fn with_provider_filter(mut self, filter: Filter<FontProvider>) -> Self {
+        if !filter.include.is_empty() {
+            self.providers = Some(filter.include);
+        }
+        self.exclude_providers = filter.exclude;
+        self
+    }
+    
+    /// Apply a parsed category filter
+    pub fn with_category_filter(mut self, filter: Filter<FontCategory>) -> Self {
+        if !filter.include.is_empty() {
+            self.categories = Some(filter.include);
+        }
+        self.exclude_categories = filter.exclude;
+        self
+    }
+    
+    /// Apply a parsed license filter
+    pub fn with_license_filter(mut self, filter: Filter<LicenseTerm>) -> Self {
+        if !filter.include.is_empty() {
+            self.licenses = Some(filter.include);
+        }
+        self.exclude_licenses = filter.exclude;
+        self
+    }
+    
+    /// Whether `provider` should be searched
+    pub fn selects_provider(&self, provider: &FontProvider) -> bool {
+        let included = self.providers
+            .as_ref()
+            .map(|list| list.contains(provider))
+            .unwrap_or(true);
+        included && !self.exclude_providers.contains(provider)
+    }
+    
+    /// The category when the filters include exactly one, for providers that
+    /// browse a category page instead of filtering
+    pub fn single_category(&self) -> Option<&FontCategory> {
+        match (&self.category, self.categories.as_deref()) {
+            (Some(category), None) => Some(category),
+            (Some(category), Some(list)) if list.iter().all(|c| c == category) => Some(category),
+            (None, Some([category])) => Some(category),
+            _ => None,
+        }
+    }
+    
+    /// Whether a font with this category passes the category filters
+    pub fn matches_category(&self, category: Option<&FontCategory>) -> bool {
+        if self.category.is_none() && self.categories.is_none() && self.exclude_categories.is_empty() {
+            return true;
+        }
+        let Some(category) = category else {
+            // Uncategorized fonts only survive pure exclusion filters
+            return self.category.is_none() && self.categories.is_none();
+        };
+        let included = match (&self.category, &self.categories) {
+            (None, None) => true,
+            (single, list) => {
+                single.as_ref() == Some(category)
+                    || list.as_ref().map(|l| l.contains(category)).unwrap_or(false)
+            }
+        };
+        included && !self.exclude_categories.contains(category)
+    }
+    
+    /// Whether `font` passes the license filters
+    pub fn matches_license(&self, font: &Font) -> bool {
+        let included = match (&self.license, &self.licenses) {
+            (None, None) => true,
+            (single, list) => {
+                single.is_some() && single.as_ref() == font.license.as_ref()
+                    || list.as_ref().is_some_and(|l| l.iter().any(|term| term.matches(font)))
+            }
+        };
+        included && !self.exclude_licenses.iter().any(|term| term.matches(font))
+    }
 }
 
 /// Search results with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResults {
     pub fonts: Vec<Font>,
+    /// Number of matches across all pages (families when `families` is populated)
     pub total: usize,
     pub query: String,
     pub providers_searched: Vec<String>,
+    /// Position of the first row of this page
+    #[serde(default)]
+    pub offset: usize,
+    /// Cursor for the next page, `None` on the last one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Cross-provider families, filled when deduplication is requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub families: Vec<CanonicalFamily>,
+    /// How each provider fared
+    #[serde(default)]
+    pub outcomes: Vec<ProviderOutcome>,
+}
+
+impl SearchResults {
+    /// Outcomes of the providers that failed
+    pub fn failures(&self) -> impl Iterator<Item = &ProviderOutcome> {
+        self.outcomes.iter().filter(|o| !o.is_ok())
+    }
+}
+
+/// How one provider fared in a search, listing or health check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderOutcome {
+    pub provider: String,
+    /// Fonts the provider returned
+    pub count: usize,
+    pub elapsed_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ProviderError>,
+}
+
+impl ProviderOutcome {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+    
+    pub fn kind(&self) -> Option<ErrorKind> {
+        self.error.as_ref().map(ProviderError::kind)
+    }
+}
+
+/// One provider offering a canonical family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontSource {
+    pub provider: FontProvider,
+    pub id: String,
+    pub variant_count: usize,
+    pub preview_url: Option<String>,
+    pub download_url: Option<String>,
+}
+
+/// A font family merged across every provider that offers it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanonicalFamily {
+    pub name: String,
+    pub category: Option<FontCategory>,
+    pub license: Option<FontLicense>,
+    /// Strongest evidence for `license` among the sources
+    #[serde(default)]
+    pub license_source: LicenseSource,
+    #[serde(default)]
+    pub license_confidence: LicenseConfidence,
+    /// Provider chosen by the configured priority order
+    pub preferred: FontProvider,
+    /// Every provider offering the family, preferred source first
+    pub sources: Vec<FontSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+}
+
+impl CanonicalFamily {
+    /// The source of the preferred provider
+    pub fn preferred_source(&self) -> &FontSource {
+        &self.sources[0]
+    }
+    
+    /// Names of every provider offering the family
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.provider.name()).collect()
+    }
+    
+    /// Whether the merged license is confirmed free for commercial use
+    pub fn commercial_use_confirmed(&self) -> bool {
+        commercial_use_confirmed(self.license.as_ref(), self.license_confidence)
+    }
 }
 
 /// Download options
@@ -318,6 +919,12 @@ pub struct DownloadOptions {
     pub formats: Vec<String>, // ttf, otf, woff, woff2
     pub weights: Option<Vec<FontWeight>>,
     pub styles: Option<Vec<FontStyle>>,
+    /// Character subsets for providers that build files per subset (latin, cyrillic, ...)
+    pub subsets: Vec<String>,
+    /// Download again even when the files are already present
+    pub force: bool,
+    /// Take a family's variable font instead of its static instances when both exist
+    pub prefer_variable: bool,
 }
 
 impl Default for DownloadOptions {
@@ -327,6 +934,90 @@ impl Default for DownloadOptions {
             formats: vec!["ttf".to_string(), "woff2".to_string()],
             weights: None,
             styles: None,
+            subsets: vec!["latin".to_string()],
+            force: false,
+            prefer_variable: false,
+        }
+    }
+}
+
+impl DownloadOptions {
+    /// Options using the configured output directory and preferred formats
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            output_dir: config.output_dir.clone(),
+            formats: config.preferred_formats.clone(),
+            ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_provider_aliases() {
+        assert_eq!("google".parse::<FontProvider>(), Ok(FontProvider::GoogleFonts));
+        assert_eq!("Bunny Fonts".parse::<FontProvider>(), Ok(FontProvider::BunnyFonts));
+        assert_eq!("1001-fonts".parse::<FontProvider>(), Ok(FontProvider::Fonts1001));
+        assert_eq!("dafont".parse::<FontProvider>(), Ok(FontProvider::DaFont));
+        assert_eq!("github".parse::<FontProvider>(), Ok(FontProvider::GitHub));
+        assert!("nowhere".parse::<FontProvider>().is_err());
+    }
+    
+    #[test]
+    fn test_parse_category_aliases() {
+        assert_eq!("sans".parse::<FontCategory>(), Ok(FontCategory::SansSerif));
+        assert_eq!("Sans-Serif".parse::<FontCategory>(), Ok(FontCategory::SansSerif));
+        assert_eq!("mono".parse::<FontCategory>(), Ok(FontCategory::Monospace));
+        assert!("blackletter".parse::<FontCategory>().is_err());
+    }
+    
+    #[test]
+    fn test_filters() {
+        let filter = Filter::<FontProvider>::parse(&["google,bunny", "!dafont"]).unwrap();
+        assert_eq!(filter.include, vec![FontProvider::GoogleFonts, FontProvider::BunnyFonts]);
+        assert_eq!(filter.exclude, vec![FontProvider::DaFont]);
+        
+        let query = SearchQuery::default()
+            .with_provider_filter(Filter::parse(&["!dafont"]).unwrap())
+            .with_category_filter(Filter::parse(&["sans,mono"]).unwrap());
+        assert!(query.selects_provider(&FontProvider::GoogleFonts));
+        assert!(!query.selects_provider(&FontProvider::DaFont));
+        assert!(query.matches_category(Some(&FontCategory::Monospace)));
+        assert!(!query.matches_category(Some(&FontCategory::Serif)));
+        assert!(!query.matches_category(None));
+        assert_eq!(query.single_category(), None);
+        
+        let query = SearchQuery::default().with_category_filter(Filter::parse(&["serif"]).unwrap());
+        assert_eq!(query.single_category(), Some(&FontCategory::Serif));
+    }
+    
+    #[test]
+    fn test_license_filter() {
+        let font = |license: FontLicense, confidence: LicenseConfidence| Font {
+            license: Some(license),
+            license_source: LicenseSource::ScrapedPage,
+            license_confidence: confidence,
+            ..Font::test("X", FontProvider::DaFont)
+        };
+        let ofl = font(FontLicense::OFL, LicenseConfidence::Verified);
+        let guessed = font(FontLicense::FreeCommercial, LicenseConfidence::Unverified);
+        let personal = font(FontLicense::Custom("Free for personal use".to_string()), LicenseConfidence::Reported);
+        
+        assert_eq!(FontLicense::from_identifier("OFL-1.1"), FontLicense::OFL);
+        assert_eq!(FontLicense::from_identifier("Apache License, Version 2.0"), FontLicense::Apache2);
+        assert_eq!(FontLicense::from_identifier("UFL-1.0"), FontLicense::Custom("UFL-1.0".to_string()));
+        
+        let query = SearchQuery::default().with_license_filter(Filter::parse(&["commercial-ok"]).unwrap());
+        assert!(query.matches_license(&ofl));
+        assert!(!query.matches_license(&guessed));
+        assert!(!query.matches_license(&personal));
+        
+        let query = SearchQuery::default().with_license_filter(Filter::parse(&["ofl,apache"]).unwrap());
+        assert!(query.matches_license(&ofl));
+        assert!(!query.matches_license(&guessed));
+        assert!("ufl".parse::<LicenseTerm>().is_err());
+    }
+}
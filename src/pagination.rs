@@ -0,0 +1,127 @@
+//! Offset/limit pagination with opaque cursors
+//!
+//! Result lists are sorted deterministically before they are sliced, so the
+//! same query always yields the same pages. A cursor records where the next
+//! page starts plus a fingerprint of the query it was issued for, so it cannot
+//! silently be replayed against a different search.
+
+use crate::error::{Error, Result};
+use crate::models::SearchQuery;
+
+/// Hex digits used for the query fingerprint
+const FINGERPRINT_LEN: usize = 16;
+
+/// Position in a paged result set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub offset: usize,
+    fingerprint: u64,
+}
+
+impl Cursor {
+    /// Cursor pointing at `offset` in the results of `query`
+    pub fn new(offset: usize, query: &SearchQuery) -> Self {
+        Self { offset, fingerprint: fingerprint(query) }
+    }
+
+    /// Opaque string form handed to callers
+    pub fn encode(&self) -> String {
+        format!("{:016x}{:x}", self.fingerprint, self.offset)
+    }
+
+    /// Parse a cursor and check it was issued for `query`
+    pub fn decode(cursor: &str, query: &SearchQuery) -> Result<Self> {
+        let invalid = || Error::InvalidCursor {
+            cursor: cursor.to_string(),
+            reason: "malformed".to_string(),
+        };
+        if cursor.len() <= FINGERPRINT_LEN || !cursor.is_ascii() {
+            return Err(invalid());
+        }
+        let (fp, offset) = cursor.split_at(FINGERPRINT_LEN);
+        let fingerprint = u64::from_str_radix(fp, 16).map_err(|_| invalid())?;
+        let offset = usize::from_str_radix(offset, 16).map_err(|_| invalid())?;
+
+        if fingerprint != self::fingerprint(query) {
+            return Err(Error::InvalidCursor {
+                cursor: cursor.to_string(),
+                reason: "issued for a different query".to_string(),
+            });
+        }
+        Ok(Self { offset, fingerprint })
+    }
+}
+
+/// Where a page starts: the cursor when given, else `query.offset`
+pub fn start_offset(query: &SearchQuery) -> Result<usize> {
+    match &query.cursor {
+        Some(cursor) => Ok(Cursor::decode(cursor, query)?.offset),
+        None => Ok(query.offset.unwrap_or(0)),
+    }
+}
+
+/// Slice one page out of `items`
+///
+/// Returns the page and the offset of the next one, if any rows remain.
+pub fn paginate<T>(items: Vec<T>, offset: usize, limit: Option<usize>) -> (Vec<T>, Option<usize>) {
+    let total = items.len();
+    let page: Vec<T> = items.into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    let end = offset.saturating_add(page.len());
+    let next = (limit.is_some() && end < total).then_some(end);
+    (page, next)
+}
+
+/// Stable hash (FNV-1a) of everything that decides which rows a query returns
+///
+/// Paging fields (`offset`, `limit`, `cursor`) are left out so the page size
+/// can change between requests.
+fn fingerprint(query: &SearchQuery) -> u64 {
+    let key = format!(
+        "{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{}",
+        query.query.trim().to_lowercase(),
+        query.providers,
+        query.exclude_providers,
+        query.category,
+        query.categories,
+        query.exclude_categories,
+        query.license,
+        query.licenses,
+        query.exclude_licenses,
+        query.dedupe,
+    );
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_do_not_overlap() {
+        let items: Vec<u32> = (0..25).collect();
+        let (first, next) = paginate(items.clone(), 0, Some(10));
+        let (second, next) = paginate(items.clone(), next.unwrap(), Some(10));
+        let (third, next) = paginate(items, next.unwrap(), Some(10));
+
+        assert_eq!(first, (0..10).collect::<Vec<_>>());
+        assert_eq!(second, (10..20).collect::<Vec<_>>());
+        assert_eq!(third, (20..25).collect::<Vec<_>>());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let query = SearchQuery { query: "inter".to_string(), ..Default::default() };
+        let cursor = Cursor::new(40, &query).encode();
+        assert_eq!(Cursor::decode(&cursor, &query).unwrap().offset, 40);
+
+        let other = SearchQuery { query: "lato".to_string(), ..Default::default() };
+        assert!(Cursor::decode(&cursor, &other).is_err());
+        assert!(Cursor::decode("not-a-cursor", &query).is_err());
+    }
+}
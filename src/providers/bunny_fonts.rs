@@ -4,11 +4,11 @@
 //! API: https://fonts.bunny.net
 
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::http::HttpClient;
 use serde::Deserialize;
-use anyhow::Result;
+use crate::error::{Error, Result};
 use std::collections::HashMap;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// Bunny Fonts API response structure
@@ -34,12 +34,12 @@ pub struct BunnyFontStyle {
 
 /// Bunny Fonts provider
 pub struct BunnyFontsProvider {
-    client: Client,
+    client: HttpClient,
     api_url: String,
 }
 
 impl BunnyFontsProvider {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
             api_url: "https://fonts.bunny.net/list".to_string(),
@@ -79,6 +79,10 @@ impl FontProviderTrait for BunnyFontsProvider {
         "Bunny Fonts"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::BunnyFonts
+    }
+    
     fn base_url(&self) -> &str {
         "https://fonts.bunny.net"
     }
@@ -100,6 +104,7 @@ impl FontProviderTrait for BunnyFontsProvider {
             .get(&self.api_url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
@@ -111,7 +116,12 @@ impl FontProviderTrait for BunnyFontsProvider {
                 provider: FontProvider::BunnyFonts,
                 category: Self::parse_category(&font.category),
                 variant_count: font.styles.len(),
+                designer: None,
+                popularity: None,
+                score: None,
                 license: Some(FontLicense::OFL),
+                license_source: LicenseSource::Catalog,
+                license_confidence: LicenseConfidence::Reported,
                 preview_url: Some(format!(
                     "https://fonts.bunny.net/family/{}",
                     id
@@ -131,15 +141,16 @@ impl FontProviderTrait for BunnyFontsProvider {
             .get(&self.api_url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
         let font = response.0.get(font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
         
         let variants: Vec<FontVariant> = font.styles
-            .iter()
-            .map(|(style_key, style)| {
+            .values()
+            .map(|style| {
                 let weight = style.weight.as_ref()
                     .map(|w| Self::parse_weight(w))
                     .unwrap_or(FontWeight::Regular);
@@ -152,6 +163,7 @@ impl FontProviderTrait for BunnyFontsProvider {
                     style: if is_italic { FontStyle::Italic } else { FontStyle::Normal },
                     file_url: None, // Bunny Fonts uses CSS delivery
                     file_format: "woff2".to_string(),
+                    axes: Vec::new(),
                 }
             })
             .collect();
@@ -163,6 +175,8 @@ impl FontProviderTrait for BunnyFontsProvider {
             category: Self::parse_category(&font.category),
             variants,
             license: Some(FontLicense::OFL),
+            license_source: LicenseSource::Catalog,
+            license_confidence: LicenseConfidence::Reported,
             designer: None,
             description: None,
             preview_url: Some(format!(
@@ -177,6 +191,7 @@ impl FontProviderTrait for BunnyFontsProvider {
             subsets: font.subsets.clone().unwrap_or_default(),
             popularity: None,
             last_modified: None,
+            date_added: None,
         })
     }
     
@@ -185,11 +200,12 @@ impl FontProviderTrait for BunnyFontsProvider {
             .get(&self.api_url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
         let font = response.0.get(font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
         
         Ok(format!(
             "https://fonts.bunny.net/css?family={}",
@@ -1,205 +1,223 @@
 //! DaFont provider - One of the largest free font repositories
 //!
-//! DaFont has 80,000+ fonts organized into categories.
+//! DaFont has no API, so fonts are scraped from its HTML. Search results,
+//! the top list and theme (category) pages share one listing layout: each
+//! font is a run of sibling `div`s holding the name and author (`lv1left`),
+//! the theme (`lv1right`), the license note (`lv2right`), the preview image
+//! and the download link. Listings are paginated; the page links tell how
+//! many pages there are. A font page (`{slug}.font`) uses the same layout
+//! for one font and adds the names of the files in its download package.
+//!
+//! Downloads are ZIP packages that are unpacked by [`crate::archive`].
 
-use anyhow::Result;
+use crate::error::{Error, Result};
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::http::HttpClient;
+use scraper::{Html, Selector};
+use std::path::Path;
 
-use crate::models::{Font, FontFamily, FontCategory, FontProvider, FontLicense, SearchQuery};
+use super::scrape::{self, absolute_url, text, Pages};
+use crate::archive;
+use crate::models::{Font, FontFamily, FontCategory, FontProvider, FontLicense, FontVariant, FontWeight, LicenseConfidence, LicenseSource, SearchQuery};
 use crate::providers::FontProviderTrait;
 
+/// Top list pages returned by `list_all`
+const TOP_PAGES: usize = 3;
+
+/// Themes that map onto a [`FontCategory`]
+const THEMES: &[(FontCategory, u32)] = &[
+    (FontCategory::SansSerif, 501),
+    (FontCategory::Serif, 502),
+    (FontCategory::Monospace, 503),
+    (FontCategory::Handwriting, 601),
+];
+
+/// One font as shown on a listing or font page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DafontEntry {
+    /// `bebas-neue`, from `bebas-neue.font`
+    pub slug: String,
+    pub name: String,
+    pub author: Option<String>,
+    /// Most specific theme (`Sans serif`)
+    pub theme: Option<String>,
+    /// `100% Free`, `Free for personal use`, ...
+    pub license_note: Option<String>,
+    pub preview_url: Option<String>,
+    pub download_url: Option<String>,
+}
+
+/// One page of a paginated listing
+#[derive(Debug, Clone, Default)]
+pub struct DafontPage {
+    pub entries: Vec<DafontEntry>,
+    /// Highest page number linked from this page
+    pub last_page: usize,
+}
+
 pub struct DafontProvider {
-    client: Client,
+    client: HttpClient,
     base_url: String,
 }
 
 impl DafontProvider {
-    pub fn new(client: Client) -> Self {
-        Self {
-            client,
-            base_url: "https://www.dafont.com".to_string(),
-        }
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, "https://www.dafont.com")
     }
-    
+
+    /// Scrape a DaFont mirror at `base_url` instead of the live site
+    pub fn with_base_url(client: HttpClient, base_url: impl Into<String>) -> Self {
+        Self { client, base_url: base_url.into() }
+    }
+
+    /// Downloads come from `dl.` next to the `www.` site; a mirror serves both
+    fn download_base(&self) -> String {
+        self.base_url.replacen("://www.", "://dl.", 1)
+    }
+
     fn parse_category(category: &str) -> Option<FontCategory> {
         match category.to_lowercase().as_str() {
             "serif" => Some(FontCategory::Serif),
             "sans-serif" | "sans serif" => Some(FontCategory::SansSerif),
-            "script" | "fancy" | "calligraphy" => Some(FontCategory::Handwriting),
+            "script" | "fancy" | "calligraphy" | "handwritten" | "brush" | "school" | "graffiti" => {
+                Some(FontCategory::Handwriting)
+            }
             "display" | "gothic" | "techno" => Some(FontCategory::Display),
-            "bitmap" | "pixel" | "monospace" => Some(FontCategory::Monospace),
+            "bitmap" | "pixel" | "pixel, bitmap" | "monospace" | "fixed width" => Some(FontCategory::Monospace),
             _ => None,
         }
     }
-    
-    /// Generate pre-indexed popular fonts from DaFont (500+ fonts)
-    fn get_popular_fonts(&self) -> Vec<Font> {
-        let popular_fonts: Vec<(&str, &str)> = vec![
-            // Script/Handwriting (100 fonts)
-            ("Pacifico", "script"), ("Lobster", "script"), ("Dancing Script", "script"),
-            ("Great Vibes", "script"), ("Sacramento", "script"), ("Alex Brush", "script"),
-            ("Allura", "script"), ("Tangerine", "script"), ("Amatic SC", "script"),
-            ("Caveat", "script"), ("Cookie", "script"), ("Kaushan Script", "script"),
-            ("Satisfy", "script"), ("Homemade Apple", "script"), ("Indie Flower", "script"),
-            ("Shadows Into Light", "script"), ("Patrick Hand", "script"), ("Architects Daughter", "script"),
-            ("Permanent Marker", "script"), ("Rock Salt", "script"), ("Yellowtail", "script"),
-            ("Courgette", "script"), ("Covered By Your Grace", "script"), ("Gloria Hallelujah", "script"),
-            ("Handlee", "script"), ("Just Another Hand", "script"), ("Reenie Beanie", "script"),
-            ("Bad Script", "script"), ("Marck Script", "script"), ("Niconne", "script"),
-            ("Norican", "script"), ("Qwigley", "script"), ("Rochester", "script"),
-            ("Rouge Script", "script"), ("Style Script", "script"), ("Zeyada", "script"),
-            ("Vibur", "script"), ("Petit Formal Script", "script"), ("League Script", "script"),
-            ("Dr Sugiyama", "script"), ("Dynalight", "script"), ("Euphoria Script", "script"),
-            ("Grand Hotel", "script"), ("Herr Von Muellerhoff", "script"), ("Italianno", "script"),
-            ("La Belle Aurore", "script"), ("Lovers Quarrel", "script"), ("Miss Fajardose", "script"),
-            ("Mr De Haviland", "script"), ("Mrs Saint Delafield", "script"), ("Mrs Sheppards", "script"),
-            ("Bilbo", "script"), ("Bilbo Swash Caps", "script"), ("Bonbon", "script"),
-            ("Butterfly Kids", "script"), ("Cedarville Cursive", "script"), ("Clicker Script", "script"),
-            ("Coming Soon", "script"), ("Crafty Girls", "script"), ("Damion", "script"),
-            ("Dawning of a New Day", "script"), ("Delius", "script"), ("Fondamento", "script"),
-            ("Gochi Hand", "script"), ("Handlee", "script"), ("Homemade Apple", "script"),
-            ("Just Me Again Down Here", "script"), ("Kalam", "script"), ("Kristi", "script"),
-            ("Leckerli One", "script"), ("Loved by the King", "script"), ("Mea Culpa", "script"),
-            ("Meddon", "script"), ("Meow Script", "script"), ("Monsieur La Doulaise", "script"),
-            ("Mr Dafoe", "script"), ("Neucha", "script"), ("Nothing You Could Do", "script"),
-            ("Over the Rainbow", "script"), ("Playball", "script"), ("Princess Sofia", "script"),
-            ("Quintessential", "script"), ("Rancho", "script"), ("Ruthie", "script"),
-            ("Sail", "script"), ("Seaweed Script", "script"), ("Short Stack", "script"),
-            ("Sofia", "script"), ("Square Peg", "script"), ("Sue Ellen Francisco", "script"),
-            ("Sunshiney", "script"), ("Swanky and Moo Moo", "script"), ("The Girl Next Door", "script"),
-            ("Vibur", "script"), ("Walter Turncoat", "script"), ("Waterfall", "script"),
-            
-            // Display (100 fonts)
-            ("Bebas Neue", "display"), ("Oswald", "display"), ("Anton", "display"),
-            ("Black Ops One", "display"), ("Bungee", "display"), ("Carter One", "display"),
-            ("Changa One", "display"), ("Graduate", "display"), ("Knewave", "display"),
-            ("Passion One", "display"), ("Plaster", "display"), ("Press Start 2P", "display"),
-            ("Russo One", "display"), ("Shrikhand", "display"), ("Teko", "display"),
-            ("Staatliches", "display"), ("Righteous", "display"), ("Monoton", "display"),
-            ("Bungee Shade", "display"), ("Alfa Slab One", "display"), ("Titan One", "display"),
-            ("Lilita One", "display"), ("Creepster", "display"), ("Bangers", "display"),
-            ("Audiowide", "display"), ("Orbitron", "display"), ("Racing Sans One", "display"),
-            ("Fugaz One", "display"), ("Germania One", "display"), ("Iceland", "display"),
-            ("Kelly Slab", "display"), ("Nosifer", "display"), ("Patua One", "display"),
-            ("Trade Winds", "display"), ("Wallpoet", "display"), ("Yeseva One", "display"),
-            ("Abril Fatface", "display"), ("Acme", "display"), ("Alfa Slab One", "display"),
-            ("Almendra Display", "display"), ("Angkor", "display"), ("Arbutus", "display"),
-            ("Astloch", "display"), ("Atomic Age", "display"), ("Aubrey", "display"),
-            ("Averia Libre", "display"), ("Bahiana", "display"), ("Bahianita", "display"),
-            ("Baloo 2", "display"), ("Balsamiq Sans", "display"), ("Barriecito", "display"),
-            ("Barrio", "display"), ("Battambang", "display"), ("Baumans", "display"),
-            ("Bevan", "display"), ("Big Shoulders Display", "display"), ("Black And White Picture", "display"),
-            ("Boogaloo", "display"), ("Bowlby One", "display"), ("Bubblegum Sans", "display"),
-            ("Buda", "display"), ("Bungee Inline", "display"), ("Bungee Outline", "display"),
-            ("Butcherman", "display"), ("Cabin Sketch", "display"), ("Caesar Dressing", "display"),
-            ("Calistoga", "display"), ("Chela One", "display"), ("Chelsea Market", "display"),
-            ("Chenla", "display"), ("Cherry Cream Soda", "display"), ("Cherry Swash", "display"),
-            ("Chewy", "display"), ("Chicle", "display"), ("Chonburi", "display"),
-            ("Cinzel Decorative", "display"), ("Coda", "display"), ("Codystar", "display"),
-            ("Coiny", "display"), ("Combo", "display"), ("Concert One", "display"),
-            ("Content", "display"), ("Contrail One", "display"), ("Corben", "display"),
-            ("Croissant One", "display"), ("Crushed", "display"), ("Cute Font", "display"),
-            ("Dangrek", "display"), ("Dela Gothic One", "display"), ("Delius Unicase", "display"),
-            ("Denk One", "display"), ("Diplomata", "display"), ("Emblema One", "display"),
-            ("Erica One", "display"), ("Ewert", "display"), ("Fascinate", "display"),
-            ("Fascinate Inline", "display"), ("Faster One", "display"), ("Federant", "display"),
-            
-            // Serif (80 fonts)
-            ("Playfair Display", "serif"), ("Lora", "serif"), ("Merriweather", "serif"),
-            ("Crimson Text", "serif"), ("Libre Baskerville", "serif"), ("Cormorant", "serif"),
-            ("EB Garamond", "serif"), ("Spectral", "serif"), ("Source Serif Pro", "serif"),
-            ("Bitter", "serif"), ("Rokkitt", "serif"), ("Vollkorn", "serif"),
-            ("Cardo", "serif"), ("Newsreader", "serif"), ("Literata", "serif"),
-            ("Bodoni Moda", "serif"), ("Young Serif", "serif"), ("Instrument Serif", "serif"),
-            ("Abhaya Libre", "serif"), ("Aleo", "serif"), ("Alike", "serif"),
-            ("Alike Angular", "serif"), ("Almendra", "serif"), ("Amethysta", "serif"),
-            ("Amiri", "serif"), ("Andada Pro", "serif"), ("Antic Didone", "serif"),
-            ("Antic Slab", "serif"), ("Arapey", "serif"), ("Arbutus Slab", "serif"),
-            ("Artifika", "serif"), ("Arvo", "serif"), ("Asar", "serif"),
-            ("Average", "serif"), ("Balthazar", "serif"), ("Belgrano", "serif"),
-            ("Bellefair", "serif"), ("Bentham", "serif"), ("BioRhyme", "serif"),
-            ("Brawler", "serif"), ("Bree Serif", "serif"), ("Buenard", "serif"),
-            ("Caladea", "serif"), ("Cambo", "serif"), ("Cantata One", "serif"),
-            ("Caudex", "serif"), ("Cinzel", "serif"), ("Cormorant Garamond", "serif"),
-            ("Cormorant Infant", "serif"), ("Cormorant SC", "serif"), ("Cormorant Unicase", "serif"),
-            ("Cormorant Upright", "serif"), ("Coustard", "serif"), ("Crete Round", "serif"),
-            ("Crimson Pro", "serif"), ("Cutive", "serif"), ("David Libre", "serif"),
-            ("Della Respira", "serif"), ("Domine", "serif"), ("Donegal One", "serif"),
-            ("Droid Serif", "serif"), ("Eczar", "serif"), ("Elsie", "serif"),
-            ("Elsie Swash Caps", "serif"), ("Enriqueta", "serif"), ("Esteban", "serif"),
-            ("Fanwood Text", "serif"), ("Fauna One", "serif"), ("Fenix", "serif"),
-            ("Fraunces", "serif"), ("Gabriela", "serif"), ("Gelasio", "serif"),
-            ("Gentium Basic", "serif"), ("Gentium Book Basic", "serif"), ("Gilda Display", "serif"),
-            ("Gloock", "serif"), ("Goudy Bookletter 1911", "serif"), ("Habibi", "serif"),
-            
-            // Sans Serif (100 fonts)
-            ("Montserrat", "sans-serif"), ("Poppins", "sans-serif"), ("Lato", "sans-serif"),
-            ("Open Sans", "sans-serif"), ("Roboto", "sans-serif"), ("Nunito", "sans-serif"),
-            ("Raleway", "sans-serif"), ("Work Sans", "sans-serif"), ("Rubik", "sans-serif"),
-            ("Karla", "sans-serif"), ("Manrope", "sans-serif"), ("Inter", "sans-serif"),
-            ("DM Sans", "sans-serif"), ("Plus Jakarta Sans", "sans-serif"), ("Outfit", "sans-serif"),
-            ("Sora", "sans-serif"), ("Lexend", "sans-serif"), ("Albert Sans", "sans-serif"),
-            ("Figtree", "sans-serif"), ("Epilogue", "sans-serif"), ("Quicksand", "sans-serif"),
-            ("Cabin", "sans-serif"), ("Catamaran", "sans-serif"), ("Comfortaa", "sans-serif"),
-            ("Didact Gothic", "sans-serif"), ("Dosis", "sans-serif"), ("Encode Sans", "sans-serif"),
-            ("Exo", "sans-serif"), ("Fira Sans", "sans-serif"), ("Gudea", "sans-serif"),
-            ("Hind", "sans-serif"), ("Josefin Sans", "sans-serif"), ("Junction", "sans-serif"),
-            ("League Spartan", "sans-serif"), ("Lexend Deca", "sans-serif"), ("Libre Franklin", "sans-serif"),
-            ("Maven Pro", "sans-serif"), ("Muli", "sans-serif"), ("Nunito Sans", "sans-serif"),
-            ("Overpass", "sans-serif"), ("Oxygen", "sans-serif"), ("PT Sans", "sans-serif"),
-            ("Questrial", "sans-serif"), ("Red Hat Display", "sans-serif"), ("Red Hat Text", "sans-serif"),
-            ("Ruda", "sans-serif"), ("Saira", "sans-serif"), ("Signika", "sans-serif"),
-            ("Source Sans Pro", "sans-serif"), ("Spartan", "sans-serif"), ("Titillium Web", "sans-serif"),
-            ("Ubuntu", "sans-serif"), ("Varela", "sans-serif"), ("Varela Round", "sans-serif"),
-            ("Abel", "sans-serif"), ("ABeeZee", "sans-serif"), ("Aclonica", "sans-serif"),
-            ("Actor", "sans-serif"), ("Advent Pro", "sans-serif"), ("Afacad", "sans-serif"),
-            ("Agdasima", "sans-serif"), ("Akshar", "sans-serif"), ("Alata", "sans-serif"),
-            ("Alatsi", "sans-serif"), ("Aldrich", "sans-serif"), ("Alegreya Sans", "sans-serif"),
-            ("Alegreya Sans SC", "sans-serif"), ("Alexandria", "sans-serif"), ("Allerta", "sans-serif"),
-            ("Allerta Stencil", "sans-serif"), ("Alumni Sans", "sans-serif"), ("Amaranth", "sans-serif"),
-            ("Amiko", "sans-serif"), ("Anaheim", "sans-serif"), ("Andika", "sans-serif"),
-            ("Anta", "sans-serif"), ("Antic", "sans-serif"), ("Antonio", "sans-serif"),
-            ("Anuphan", "sans-serif"), ("Archivo", "sans-serif"), ("Archivo Black", "sans-serif"),
-            ("Archivo Narrow", "sans-serif"), ("Arimo", "sans-serif"), ("Armata", "sans-serif"),
-            ("Arsenal", "sans-serif"), ("Arya", "sans-serif"), ("Asap", "sans-serif"),
-            ("Asap Condensed", "sans-serif"), ("Assistant", "sans-serif"), ("Asul", "sans-serif"),
-            ("Athiti", "sans-serif"), ("Atkinson Hyperlegible", "sans-serif"), ("Average Sans", "sans-serif"),
-            ("Averia Sans Libre", "sans-serif"), ("Azeret Mono", "sans-serif"), ("B612", "sans-serif"),
-            ("Bai Jamjuree", "sans-serif"), ("Barlow", "sans-serif"), ("Barlow Condensed", "sans-serif"),
-            ("Basic", "sans-serif"), ("Be Vietnam Pro", "sans-serif"), ("Belleza", "sans-serif"),
-            
-            // Monospace (40 fonts)
-            ("Fira Code", "monospace"), ("JetBrains Mono", "monospace"), ("Source Code Pro", "monospace"),
-            ("Roboto Mono", "monospace"), ("IBM Plex Mono", "monospace"), ("Ubuntu Mono", "monospace"),
-            ("Space Mono", "monospace"), ("Inconsolata", "monospace"), ("Anonymous Pro", "monospace"),
-            ("Cousine", "monospace"), ("Share Tech Mono", "monospace"), ("Overpass Mono", "monospace"),
-            ("Azeret Mono", "monospace"), ("Martian Mono", "monospace"), ("Red Hat Mono", "monospace"),
-            ("DM Mono", "monospace"), ("Fira Mono", "monospace"), ("PT Mono", "monospace"),
-            ("Cutive Mono", "monospace"), ("Nova Mono", "monospace"), ("Oxygen Mono", "monospace"),
-            ("Courier Prime", "monospace"), ("B612 Mono", "monospace"), ("Nanum Gothic Coding", "monospace"),
-            ("Geist Mono", "monospace"), ("Hack", "monospace"), ("Victor Mono", "monospace"),
-            ("Major Mono Display", "monospace"), ("Xanh Mono", "monospace"), ("Chivo Mono", "monospace"),
-            ("Silkscreen", "monospace"), ("VCR OSD Mono", "monospace"), ("Press Start", "monospace"),
-            ("Pixel", "monospace"), ("04b03", "monospace"), ("Arcade", "monospace"),
-            ("Minecraftia", "monospace"), ("Dogica", "monospace"), ("Commodore 64", "monospace"),
-            ("ZX Spectrum", "monospace"),
-        ];
-        
-        popular_fonts.into_iter().map(|(name, category)| {
-            let id = name.to_lowercase().replace(' ', "-").replace(|c: char| !c.is_alphanumeric() && c != '-', "");
-            Font {
-                id: format!("dafont-{}", id),
-                name: name.to_string(),
-                provider: FontProvider::DaFont,
-                category: Self::parse_category(category),
-                variant_count: 1,
-                license: Some(FontLicense::FreeCommercial),
-                preview_url: Some(format!("{}/{}.font", self.base_url, id)),
-                download_url: Some(format!("{}/dl/?f={}", self.base_url, id)),
-            }
-        }).collect()
+
+    /// Map a license note to a license; only fully free notes are known licenses
+    fn parse_license(note: &str) -> FontLicense {
+        match note.to_lowercase().as_str() {
+            "100% free" | "free for commercial use" => FontLicense::FreeCommercial,
+            "public domain" => FontLicense::PublicDomain,
+            _ => FontLicense::Custom(note.to_string()),
+        }
+    }
+
+    /// Fetch a listing (`search.php?q=...`, `top.php`, `theme.php?cat=...`)
+    /// and the pages of it that `pages` allows
+    async fn fetch_listing(&self, path: &str, pages: Pages) -> Result<Vec<DafontEntry>> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        scrape::fetch_pages(
+            &self.client,
+            |page| format!("{}/{}{}page={}", self.base_url, path, separator, page),
+            pages,
+            |html| {
+                let page = parse_listing(html, &self.base_url);
+                (page.entries, page.last_page)
+            },
+            |entry| entry.slug.clone(),
+        ).await
+    }
+
+    fn to_font(&self, entry: DafontEntry, popularity: Option<u32>) -> Font {
+        Font {
+            id: format!("dafont-{}", entry.slug),
+            name: entry.name,
+            provider: FontProvider::DaFont,
+            category: entry.theme.as_deref().and_then(Self::parse_category),
+            variant_count: 1,
+            designer: entry.author,
+            popularity,
+            score: None,
+            license: entry.license_note.as_deref().map(Self::parse_license),
+            // Without a note there is only the site's catalog-wide terms
+            license_source: if entry.license_note.is_some() { LicenseSource::ScrapedPage } else { LicenseSource::Catalog },
+            license_confidence: if entry.license_note.is_some() {
+                LicenseConfidence::Reported
+            } else {
+                LicenseConfidence::Unverified
+            },
+            preview_url: entry.preview_url,
+            download_url: entry.download_url,
+        }
+    }
+}
+
+/// Parse a listing page
+pub fn parse_listing(html: &str, base_url: &str) -> DafontPage {
+    let document = Html::parse_document(html);
+    let last_page = scrape::last_page(&document, "div.noindex a[href*='page=']", "page");
+    DafontPage { entries: entries(&document, base_url, None), last_page }
+}
+
+/// Parse the font page of `slug`, returning its entry and the file names in its package
+pub fn parse_font_page(html: &str, slug: &str, base_url: &str) -> Option<(DafontEntry, Vec<String>)> {
+    let document = Html::parse_document(html);
+    let entry = entries(&document, base_url, Some(slug)).into_iter().next()?;
+    let filenames = Selector::parse("div.filename").expect("valid selector");
+    let files = document.select(&filenames)
+        .map(text)
+        .filter(|name| archive::font_format(Path::new(name)).is_some())
+        .collect();
+    Some((entry, files))
+}
+
+/// Entries in document order; each `lv1left` starts a new one
+///
+/// A font page does not link the font to itself, so its slug is passed in.
+fn entries(document: &Html, base_url: &str, page_slug: Option<&str>) -> Vec<DafontEntry> {
+    let blocks = Selector::parse("div.lv1left, div.lv1right, div.lv2right, div.preview, div.dlbox").expect("valid selector");
+    let links = Selector::parse("a").expect("valid selector");
+    let strong = Selector::parse("strong").expect("valid selector");
+    let note = Selector::parse("a.help").expect("valid selector");
+    let download = Selector::parse("a.dl").expect("valid selector");
+
+    let mut entries: Vec<DafontEntry> = Vec::new();
+    for block in document.select(&blocks) {
+        let class = block.value().attr("class").unwrap_or_default();
+        if class.split_whitespace().any(|c| c == "lv1left") {
+            let slug = block.select(&links)
+                .find_map(|a| a.value().attr("href")?.strip_suffix(".font").map(str::to_string))
+                .or_else(|| page_slug.map(str::to_string));
+            let name = block.select(&strong).next().map(text);
+            let (Some(slug), Some(name)) = (slug, name) else {
+                continue;
+            };
+            // The author link is the one that is not the font page
+            let author = block.select(&links)
+                .find(|a| !a.value().attr("href").unwrap_or_default().ends_with(".font"))
+                .map(text);
+            entries.push(DafontEntry {
+                slug,
+                name,
+                author,
+                theme: None,
+                license_note: None,
+                preview_url: None,
+                download_url: None,
+            });
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+        if class.contains("lv1right") {
+            entry.theme = block.select(&links).last().map(text);
+        } else if class.contains("lv2right") {
+            entry.license_note = block.select(&note).next().map(text).filter(|n| !n.is_empty());
+        } else if class.contains("preview") {
+            entry.preview_url = block.value().attr("style")
+                .and_then(background_image)
+                .map(|url| absolute_url(url, base_url));
+        } else if class.contains("dlbox") {
+            entry.download_url = block.select(&download)
+                .filter_map(|a| a.value().attr("href"))
+                .find(|href| href.contains("/dl/?f="))
+                .map(|href| absolute_url(href, base_url));
+        }
     }
+    entries
+}
+
+/// The URL in `background-image:url(/img/preview/b/e/bebas_neue0.png)`
+fn background_image(style: &str) -> Option<&str> {
+    let start = style.find("url(")? + 4;
+    let end = start + style[start..].find(')')?;
+    Some(style[start..end].trim_matches(['"', '\'']))
 }
 
 #[async_trait]
@@ -207,59 +225,99 @@ impl FontProviderTrait for DafontProvider {
     fn name(&self) -> &str {
         "DaFont"
     }
-    
+
+    fn provider(&self) -> FontProvider {
+        FontProvider::DaFont
+    }
+
     fn base_url(&self) -> &str {
         &self.base_url
     }
-    
+
+    /// `list_all` is only the top list; everything else is found by searching
+    fn has_full_catalog(&self) -> bool { false }
+
     async fn search(&self, query: &SearchQuery) -> Result<Vec<Font>> {
-        let all_fonts = self.get_popular_fonts();
-        let query_lower = query.query.to_lowercase();
-        
-        if query_lower.is_empty() {
-            return Ok(all_fonts);
-        }
-        
-        Ok(all_fonts
-            .into_iter()
-            .filter(|font| {
-                font.name.to_lowercase().contains(&query_lower) ||
-                font.category.as_ref().map(|c| format!("{:?}", c).to_lowercase().contains(&query_lower)).unwrap_or(false)
-            })
-            .collect())
+        let path = if !query.query.trim().is_empty() {
+            let mut url = reqwest::Url::parse(&format!("{}/search.php", self.base_url))
+                .map_err(|e| Error::Other(e.into()))?;
+            url.query_pairs_mut().append_pair("q", query.query.trim());
+            format!("search.php?{}", url.query().unwrap_or_default())
+        } else if let Some((_, theme)) = THEMES.iter().find(|(c, _)| query.single_category() == Some(c)) {
+            format!("theme.php?cat={}", theme)
+        } else {
+            return self.list_all().await;
+        };
+
+        let entries = self.fetch_listing(&path, Pages::Search).await?;
+        Ok(entries.into_iter().map(|e| self.to_font(e, None)).collect())
     }
-    
+
+    /// The most downloaded fonts, ranked
     async fn list_all(&self) -> Result<Vec<Font>> {
-        Ok(self.get_popular_fonts())
+        let entries = self.fetch_listing("top.php", Pages::Catalog(TOP_PAGES)).await?;
+        Ok(entries.into_iter()
+            .enumerate()
+            .map(|(rank, e)| self.to_font(e, Some(rank as u32 + 1)))
+            .collect())
     }
-    
+
     async fn get_font_family(&self, font_id: &str) -> Result<FontFamily> {
-        let fonts = self.get_popular_fonts();
-        let font = fonts.into_iter().find(|f| f.id == font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
-        
+        let slug = font_id.strip_prefix("dafont-").unwrap_or(font_id);
+        let html = match scrape::fetch(&self.client, &format!("{}/{}.font", self.base_url, slug)).await {
+            Err(Error::HttpStatus { status: 404, .. }) => return Err(Error::font_not_found(self.name(), font_id)),
+            result => result?,
+        };
+        let (entry, files) = parse_font_page(&html, slug, &self.base_url)
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
+
+        // The package is downloaded whole, so the variants carry no URLs
+        let mut variants: Vec<FontVariant> = Vec::new();
+        for file in &files {
+            let path = Path::new(file);
+            let info = archive::parse_font_file(path);
+            let variant = FontVariant {
+                weight: info.weight.unwrap_or(FontWeight::Regular),
+                style: info.style,
+                file_url: None,
+                file_format: archive::font_format(path).unwrap_or_default().to_string(),
+                axes: Vec::new(),
+            };
+            if !variants.iter().any(|v| {
+                v.weight == variant.weight && v.style == variant.style && v.file_format == variant.file_format
+            }) {
+                variants.push(variant);
+            }
+        }
+
+        let font = self.to_font(entry, None);
         Ok(FontFamily {
-            id: font.id.clone(),
-            name: font.name.clone(),
+            id: font.id,
+            name: font.name,
             provider: FontProvider::DaFont,
-            category: font.category.clone(),
-            variants: vec![],
-            subsets: vec!["latin".to_string()],
-            license: font.license.clone(),
-            designer: None,
+            category: font.category,
+            variants,
+            subsets: Vec::new(),
+            license: font.license,
+            license_source: font.license_source,
+            license_confidence: font.license_confidence,
+            designer: font.designer,
             description: None,
-            preview_url: font.preview_url.clone(),
-            download_url: font.download_url.clone(),
-            languages: vec!["Latin".to_string()],
+            preview_url: Some(format!("{}/{}.font", self.base_url, slug)),
+            download_url: font.download_url,
+            languages: Vec::new(),
             last_modified: None,
+            date_added: None,
             popularity: None,
         })
     }
-    
+
     async fn get_download_url(&self, font_id: &str) -> Result<String> {
-        Ok(format!("{}/dl/?f={}", self.base_url, font_id.replace("dafont-", "")))
+        // Download names use underscores where font page slugs use dashes
+        let slug = font_id.strip_prefix("dafont-").unwrap_or(font_id);
+        Ok(format!("{}/dl/?f={}", self.download_base(), slug.replace('-', "_")))
     }
-    
+
     async fn health_check(&self) -> Result<bool> {
         Ok(self.client.get(&self.base_url)
             .send()
@@ -268,3 +326,69 @@ impl FontProviderTrait for DafontProvider {
             .unwrap_or(false))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://www.dafont.com";
+
+    #[test]
+    fn test_parse_listing_fixture() {
+        let page = parse_listing(include_str!("fixtures/dafont_search.html"), BASE);
+        assert_eq!(page.last_page, 3);
+        assert_eq!(page.entries.len(), 3);
+
+        let bebas = &page.entries[0];
+        assert_eq!(bebas.slug, "bebas-neue");
+        assert_eq!(bebas.name, "Bebas Neue");
+        assert_eq!(bebas.author.as_deref(), Some("Dharma Type"));
+        assert_eq!(bebas.theme.as_deref(), Some("Sans serif"));
+        assert_eq!(bebas.license_note.as_deref(), Some("100% Free"));
+        assert_eq!(bebas.preview_url.as_deref(), Some("https://www.dafont.com/img/preview/b/e/bebas_neue0.png"));
+        assert_eq!(bebas.download_url.as_deref(), Some("https://dl.dafont.com/dl/?f=bebas_neue"));
+
+        // The donation link is not mistaken for the download
+        assert_eq!(page.entries[1].download_url.as_deref(), Some("https://dl.dafont.com/dl/?f=bebas_kai"));
+        assert_eq!(DafontProvider::parse_license(page.entries[1].license_note.as_deref().unwrap()),
+            FontLicense::Custom("Free for personal use".to_string()));
+        assert_eq!(DafontProvider::parse_category(page.entries[2].theme.as_deref().unwrap()), Some(FontCategory::Handwriting));
+    }
+
+    #[test]
+    fn test_parse_font_page_fixture() {
+        let (entry, files) = parse_font_page(include_str!("fixtures/dafont_font.html"), "bebas-neue", BASE).unwrap();
+        assert_eq!(entry.slug, "bebas-neue");
+        assert_eq!(entry.name, "Bebas Neue");
+        assert_eq!(entry.author.as_deref(), Some("Dharma Type"));
+        assert_eq!(entry.download_url.as_deref(), Some("https://dl.dafont.com/dl/?f=bebas_neue"));
+        assert_eq!(files, ["BebasNeue-Regular.otf", "BebasNeue-Regular.ttf"]);
+        assert!(parse_font_page("<html><body>Not found</body></html>", "missing", BASE).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_url_follows_base_url() {
+        let client = crate::providers::create_http_client().unwrap();
+        let live = DafontProvider::new(client.clone());
+        let mirror = DafontProvider::with_base_url(client, "http://127.0.0.1:8080");
+        assert_eq!(live.get_download_url("dafont-bebas-neue").await.unwrap(), "https://dl.dafont.com/dl/?f=bebas_neue");
+        assert_eq!(mirror.get_download_url("dafont-bebas-neue").await.unwrap(), "http://127.0.0.1:8080/dl/?f=bebas_neue");
+    }
+
+    #[tokio::test]
+    async fn test_search_all_reaches_search_page() {
+        let base_url = scrape::serve(vec![
+            ("/search.php", 200, include_str!("fixtures/dafont_search.html").to_string()),
+            ("/top.php", 200, "<html><body></body></html>".to_string()),
+        ]).await;
+        let client = crate::providers::create_http_client().unwrap();
+        let mut registry = crate::providers::ProviderRegistry::new();
+        registry.register(std::sync::Arc::new(DafontProvider::with_base_url(client, base_url)));
+
+        let query = SearchQuery { query: "bebas".to_string(), ..Default::default() };
+        let results = registry.search_all(&query).await.unwrap();
+        let ids: Vec<&str> = results.fonts.iter().map(|f| f.id.as_str()).collect();
+        assert!(ids.contains(&"dafont-bebas-neue"), "{:?}", ids);
+        assert!(ids.contains(&"dafont-bebas-kai"), "{:?}", ids);
+    }
+}
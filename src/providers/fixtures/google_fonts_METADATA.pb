@@ -0,0 +1,42 @@
+# proto-file: google/fonts/tools/fonts_public.proto
+# proto-message: FamilyProto
+
+name: "Roboto Flex"
+designer: "Font Bureau, David Berlow"
+license: "OFL"
+category: "SANS_SERIF"
+date_added: "2022-03-07"
+fonts {
+  name: "Roboto Flex"
+  style: "normal"
+  weight: 400
+  filename: "RobotoFlex[GRAD,wdth,wght].ttf"
+  post_script_name: "RobotoFlex-Regular"
+  full_name: "Roboto Flex Regular"
+  copyright: "Copyright 2017 The Roboto Flex Project Authors "
+    "(https://github.com/googlefonts/roboto-flex) \302\251"
+}
+subsets: "cyrillic"
+subsets: "latin"
+subsets: "latin-ext"
+subsets: "menu"
+axes {
+  tag: "GRAD"
+  min_value: -200.0
+  max_value: 150.0
+}
+axes {
+  tag: "wdth"
+  min_value: 25.0
+  max_value: 151.0
+}
+axes {
+  tag: "wght"
+  min_value: 100.0
+  max_value: 1000.0
+}
+source {
+  repository_url: "https://github.com/googlefonts/roboto-flex"
+  commit: "c8c3a5b1d1e5e2a4b6a2b1f3e4d5c6b7a8f9e0d1"
+}
+classifications: "DISPLAY"
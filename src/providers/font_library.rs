@@ -4,10 +4,10 @@
 //! API: https://fontlibrary.org/en/catalogue
 
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::http::HttpClient;
 use serde::Deserialize;
-use anyhow::Result;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::error::{Error, Result};
+use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// Font Library API font response
@@ -30,30 +30,12 @@ pub struct FontLibraryStyle {
 
 /// Font Library provider
 pub struct FontLibraryProvider {
-    client: Client,
-    api_url: String,
+    client: HttpClient,
 }
 
 impl FontLibraryProvider {
-    pub fn new(client: Client) -> Self {
-        Self {
-            client,
-            // Font Library has a catalogue JSON endpoint
-            api_url: "https://fontlibrary.org/api/fonts".to_string(),
-        }
-    }
-    
-    fn parse_category(category: &Option<String>) -> Option<FontCategory> {
-        category.as_ref().and_then(|c| {
-            match c.to_lowercase().as_str() {
-                "serif" => Some(FontCategory::Serif),
-                "sans-serif" | "sans" => Some(FontCategory::SansSerif),
-                "display" | "decorative" => Some(FontCategory::Display),
-                "handwriting" | "script" => Some(FontCategory::Handwriting),
-                "monospace" | "mono" => Some(FontCategory::Monospace),
-                _ => None,
-            }
-        })
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
     }
 }
 
@@ -63,6 +45,10 @@ impl FontProviderTrait for FontLibraryProvider {
         "Font Library"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::FontLibrary
+    }
+    
     fn base_url(&self) -> &str {
         "https://fontlibrary.org"
     }
@@ -92,7 +78,12 @@ impl FontProviderTrait for FontLibraryProvider {
                 provider: FontProvider::FontLibrary,
                 category: Some(category),
                 variant_count: 4,
+                designer: None,
+                popularity: None,
+                score: None,
                 license: Some(FontLicense::OFL),
+                license_source: LicenseSource::Catalog,
+                license_confidence: LicenseConfidence::Reported,
                 preview_url: Some(format!(
                     "https://fontlibrary.org/en/font/{}",
                     id
@@ -111,7 +102,7 @@ impl FontProviderTrait for FontLibraryProvider {
         let fonts = self.list_all().await?;
         let font = fonts.into_iter()
             .find(|f| f.id == font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
         
         Ok(FontFamily {
             id: font.id,
@@ -124,9 +115,12 @@ impl FontProviderTrait for FontLibraryProvider {
                     style: FontStyle::Normal,
                     file_url: font.download_url.clone(),
                     file_format: "ttf".to_string(),
+                    axes: Vec::new(),
                 },
             ],
             license: Some(FontLicense::OFL),
+            license_source: LicenseSource::Catalog,
+            license_confidence: LicenseConfidence::Reported,
             designer: None,
             description: None,
             preview_url: font.preview_url,
@@ -135,6 +129,7 @@ impl FontProviderTrait for FontLibraryProvider {
             subsets: vec!["latin".to_string()],
             popularity: None,
             last_modified: None,
+            date_added: None,
         })
     }
     
@@ -4,10 +4,10 @@
 //! API: https://www.fontshare.com
 
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::http::HttpClient;
 use serde::Deserialize;
-use anyhow::Result;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::error::Result;
+use crate::models::{Font, FontAxis, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// FontShare API font response
@@ -31,9 +31,22 @@ pub struct FontshareStyle {
     pub name: String,
     pub weight: Option<i32>,
     pub is_italic: Option<bool>,
+    pub is_variable: Option<bool>,
+    #[serde(default)]
+    pub axes: Vec<FontshareAxis>,
     pub file: Option<FontshareFile>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FontshareAxis {
+    pub name: Option<String>,
+    /// Axis tag (`wght`)
+    pub property: String,
+    pub range_min: f32,
+    pub range_default: f32,
+    pub range_max: f32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct FontshareFile {
     pub url: Option<String>,
@@ -46,12 +59,12 @@ pub struct FontshareDesigner {
 
 /// FontShare provider
 pub struct FontshareProvider {
-    client: Client,
+    client: HttpClient,
     api_url: String,
 }
 
 impl FontshareProvider {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
             api_url: "https://api.fontshare.com/v2/fonts".to_string(),
@@ -78,6 +91,10 @@ impl FontProviderTrait for FontshareProvider {
         "FontShare"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::FontShare
+    }
+    
     fn base_url(&self) -> &str {
         "https://www.fontshare.com"
     }
@@ -100,13 +117,8 @@ impl FontProviderTrait for FontshareProvider {
         let response = self.client
             .get(&self.api_url)
             .send()
-            .await?;
-        
-        // Check if we get a valid response
-        if !response.status().is_success() {
-            // Return empty if API is not accessible
-            return Ok(Vec::new());
-        }
+            .await?
+            .error_for_status()?;
         
         let text = response.text().await?;
         
@@ -121,7 +133,12 @@ impl FontProviderTrait for FontshareProvider {
                         provider: FontProvider::FontShare,
                         category: Self::parse_category(&f.category),
                         variant_count: f.styles.len(),
+                        designer: f.designer.as_ref().and_then(|d| d.name.clone()),
+                        popularity: None,
+                        score: None,
                         license: Some(FontLicense::FreeCommercial),
+                        license_source: LicenseSource::Catalog,
+                        license_confidence: LicenseConfidence::Reported,
                         preview_url: Some(format!(
                             "https://www.fontshare.com/fonts/{}",
                             f.slug
@@ -147,7 +164,12 @@ impl FontProviderTrait for FontshareProvider {
                                 provider: FontProvider::FontShare,
                                 category: Self::parse_category(&f.category),
                                 variant_count: f.styles.len(),
+                                designer: f.designer.as_ref().and_then(|d| d.name.clone()),
+                                popularity: None,
+                                score: None,
                                 license: Some(FontLicense::FreeCommercial),
+                                license_source: LicenseSource::Catalog,
+                                license_confidence: LicenseConfidence::Reported,
                                 preview_url: Some(format!(
                                     "https://www.fontshare.com/fonts/{}",
                                     f.slug
@@ -161,7 +183,7 @@ impl FontProviderTrait for FontshareProvider {
                         
                         Ok(fonts)
                     }
-                    Err(_) => Ok(Vec::new()),
+                    Err(e) => Err(e.into()),
                 }
             }
         }
@@ -173,9 +195,22 @@ impl FontProviderTrait for FontshareProvider {
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
+        // Variable styles without axis data span the static weights
+        let static_weights: Vec<u16> = response.styles.iter()
+            .filter(|s| !s.is_variable.unwrap_or(false))
+            .filter_map(|s| s.weight.map(|w| w as u16))
+            .collect();
+        let weight_axis = match (static_weights.iter().min(), static_weights.iter().max()) {
+            (Some(&min), Some(&max)) if min < max => {
+                Some(FontAxis::new("wght", min as f32, 400f32.clamp(min as f32, max as f32), max as f32))
+            }
+            _ => None,
+        };
+        
         let variants: Vec<FontVariant> = response.styles
             .iter()
             .map(|s| {
@@ -188,11 +223,25 @@ impl FontProviderTrait for FontshareProvider {
                     FontStyle::Normal
                 };
                 
+                let axes = if !s.axes.is_empty() {
+                    s.axes.iter()
+                        .map(|a| FontAxis {
+                            name: a.name.clone(),
+                            ..FontAxis::new(&a.property, a.range_min, a.range_default, a.range_max)
+                        })
+                        .collect()
+                } else if s.is_variable.unwrap_or(false) {
+                    weight_axis.iter().cloned().collect()
+                } else {
+                    Vec::new()
+                };
+                
                 FontVariant {
                     weight,
                     style,
                     file_url: s.file.as_ref().and_then(|f| f.url.clone()),
                     file_format: "ttf".to_string(),
+                    axes,
                 }
             })
             .collect();
@@ -204,6 +253,8 @@ impl FontProviderTrait for FontshareProvider {
             category: Self::parse_category(&response.category),
             variants,
             license: Some(FontLicense::FreeCommercial),
+            license_source: LicenseSource::Catalog,
+            license_confidence: LicenseConfidence::Reported,
             designer: response.designer.and_then(|d| d.name),
             description: response.description,
             preview_url: Some(format!(
@@ -218,6 +269,7 @@ impl FontProviderTrait for FontshareProvider {
             subsets: vec!["latin".to_string()],
             popularity: None,
             last_modified: None,
+            date_added: None,
         })
     }
     
@@ -4,10 +4,11 @@
 //! API: https://api.fontsource.org/v1/fonts
 
 use async_trait::async_trait;
-use reqwest::Client;
-use serde::Deserialize;
-use anyhow::Result;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::http::HttpClient;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use crate::error::Result;
+use crate::models::{Font, FontAxis, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// Fontsource API font response
@@ -25,22 +26,98 @@ pub struct FontsourceFont {
     #[serde(rename = "lastModified")]
     pub last_modified: Option<String>,
     pub version: Option<String>,
+    #[serde(default)]
+    pub license: Option<FontsourceLicense>,
+}
+
+/// License as sent by the API: an id in listings, details for a single font
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum FontsourceLicense {
+    Id(String),
+    Details {
+        #[serde(rename = "type")]
+        id: String,
+        url: Option<String>,
+        attribution: Option<String>,
+    },
+}
+
+impl FontsourceFont {
+    /// The license the API names, else the OFL that most of the catalog uses
+    fn license(&self) -> (FontLicense, LicenseSource, LicenseConfidence) {
+        match &self.license {
+            Some(FontsourceLicense::Id(id)) | Some(FontsourceLicense::Details { id, .. }) => {
+                (FontLicense::from_identifier(id), LicenseSource::ProviderApi, LicenseConfidence::Verified)
+            }
+            None => (FontLicense::OFL, LicenseSource::Catalog, LicenseConfidence::Unverified),
+        }
+    }
+}
+
+/// Fontsource variable font metadata (`/v1/variable/{id}`)
+#[derive(Debug, Deserialize)]
+pub struct FontsourceVariable {
+    pub axes: BTreeMap<String, FontsourceAxis>,
+}
+
+/// Axis range; the API sends the numbers as strings
+#[derive(Debug, Deserialize)]
+pub struct FontsourceAxis {
+    #[serde(deserialize_with = "number")]
+    pub default: f32,
+    #[serde(deserialize_with = "number")]
+    pub min: f32,
+    #[serde(deserialize_with = "number")]
+    pub max: f32,
+}
+
+fn number<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<f32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Float(f32),
+        Text(String),
+    }
+    match Number::deserialize(deserializer)? {
+        Number::Float(n) => Ok(n),
+        Number::Text(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
 }
 
 /// Fontsource provider
 pub struct FontsourceProvider {
-    client: Client,
+    client: HttpClient,
     api_url: String,
 }
 
 impl FontsourceProvider {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
             api_url: "https://api.fontsource.org/v1/fonts".to_string(),
         }
     }
     
+    /// Axes of a variable family, or none if the metadata is unavailable
+    async fn variable_axes(&self, font_id: &str) -> Vec<FontAxis> {
+        let url = format!("https://api.fontsource.org/v1/variable/{}", font_id);
+        let response = match self.client.get(&url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.json::<FontsourceVariable>().await,
+            Err(e) => Err(e),
+        };
+        match response {
+            Ok(variable) => variable.axes
+                .into_iter()
+                .map(|(tag, a)| FontAxis::new(&tag, a.min, a.default, a.max))
+                .collect(),
+            Err(e) => {
+                tracing::debug!("No variable axes for {}: {}", font_id, e);
+                Vec::new()
+            }
+        }
+    }
+    
     fn parse_category(category: &str) -> Option<FontCategory> {
         match category.to_lowercase().as_str() {
             "serif" => Some(FontCategory::Serif),
@@ -59,6 +136,10 @@ impl FontProviderTrait for FontsourceProvider {
         "Fontsource"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::Fontsource
+    }
+    
     fn base_url(&self) -> &str {
         "https://fontsource.org"
     }
@@ -80,6 +161,7 @@ impl FontProviderTrait for FontsourceProvider {
             .get(&self.api_url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
@@ -87,13 +169,19 @@ impl FontProviderTrait for FontsourceProvider {
             .into_iter()
             .map(|f| {
                 let variant_count = f.weights.len() * f.styles.len();
+                let (license, license_source, license_confidence) = f.license();
                 Font {
                     id: f.id.clone(),
                     name: f.family.clone(),
                     provider: FontProvider::Fontsource,
                     category: Self::parse_category(&f.category),
                     variant_count,
-                    license: Some(FontLicense::OFL),
+                    designer: None,
+                    popularity: None,
+                    score: None,
+                    license: Some(license),
+                    license_source,
+                    license_confidence,
                     preview_url: Some(format!(
                         "https://fontsource.org/fonts/{}",
                         f.id
@@ -115,6 +203,7 @@ impl FontProviderTrait for FontsourceProvider {
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
@@ -128,25 +217,59 @@ impl FontProviderTrait for FontsourceProvider {
                     FontStyle::Normal
                 };
                 
-                variants.push(FontVariant {
-                    weight: font_weight,
-                    style: font_style,
-                    file_url: Some(format!(
-                        "https://cdn.jsdelivr.net/npm/@fontsource/{}/files/{}-{}-latin-{}.woff2",
-                        response.id, response.id, weight, style
-                    )),
-                    file_format: "woff2".to_string(),
-                });
+                for format in ["woff2", "woff"] {
+                    variants.push(FontVariant {
+                        weight: font_weight,
+                        style: font_style,
+                        file_url: Some(format!(
+                            "https://cdn.jsdelivr.net/npm/@fontsource/{}/files/{}-latin-{}-{}.{}",
+                            response.id, response.id, weight, style, format
+                        )),
+                        file_format: format.to_string(),
+                        axes: Vec::new(),
+                    });
+                }
+            }
+        }
+        
+        // Variable builds live in the `@fontsource-variable` packages, as one
+        // file per style covering a single axis or, with several, all (`full`)
+        if response.variable.unwrap_or(false) {
+            let axes = self.variable_axes(&response.id).await;
+            let axis_key = match axes.as_slice() {
+                [] => None,
+                [axis] => Some(axis.tag.clone()),
+                _ => Some("full".to_string()),
+            };
+            if let Some(axis_key) = axis_key {
+                let default_weight = axes.iter()
+                    .find(|a| a.tag == "wght")
+                    .map_or(400, |a| a.default as u16);
+                for style in &response.styles {
+                    variants.push(FontVariant {
+                        weight: FontWeight::from_numeric(default_weight),
+                        style: if style == "italic" { FontStyle::Italic } else { FontStyle::Normal },
+                        file_url: Some(format!(
+                            "https://cdn.jsdelivr.net/npm/@fontsource-variable/{}/files/{}-latin-{}-{}.woff2",
+                            response.id, response.id, axis_key, style
+                        )),
+                        file_format: "woff2".to_string(),
+                        axes: axes.clone(),
+                    });
+                }
             }
         }
         
+        let (license, license_source, license_confidence) = response.license();
         Ok(FontFamily {
             id: response.id.clone(),
             name: response.family.clone(),
             provider: FontProvider::Fontsource,
             category: Self::parse_category(&response.category),
             variants,
-            license: Some(FontLicense::OFL),
+            license: Some(license),
+            license_source,
+            license_confidence,
             designer: None,
             description: None,
             preview_url: Some(format!(
@@ -161,6 +284,7 @@ impl FontProviderTrait for FontsourceProvider {
             subsets: response.subsets,
             popularity: None,
             last_modified: response.last_modified,
+            date_added: None,
         })
     }
     
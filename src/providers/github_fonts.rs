@@ -4,19 +4,18 @@
 //! Includes Adobe Fonts, Noto Fonts, popular coding fonts, etc.
 
 use async_trait::async_trait;
-use reqwest::Client;
-use serde::Deserialize;
-use anyhow::Result;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::http::HttpClient;
+use crate::error::{Error, Result};
+use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// GitHub Fonts provider
 pub struct GitHubFontsProvider {
-    client: Client,
+    client: HttpClient,
 }
 
 impl GitHubFontsProvider {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self { client }
     }
 }
@@ -27,6 +26,10 @@ impl FontProviderTrait for GitHubFontsProvider {
         "GitHub Fonts"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::GitHub
+    }
+    
     fn base_url(&self) -> &str {
         "https://github.com"
     }
@@ -54,7 +57,13 @@ impl FontProviderTrait for GitHubFontsProvider {
                 provider: FontProvider::GitHub,
                 category: Some(category),
                 variant_count: 4,
+                designer: None,
+                popularity: None,
+                score: None,
                 license: Some(license),
+                // Taken from each repository's license file when the list was compiled
+                license_source: LicenseSource::Catalog,
+                license_confidence: LicenseConfidence::Reported,
                 preview_url: Some(format!("https://github.com/{}", repo)),
                 download_url: Some(format!(
                     "https://github.com/{}/releases/latest",
@@ -70,7 +79,7 @@ impl FontProviderTrait for GitHubFontsProvider {
         let fonts = self.list_all().await?;
         let font = fonts.into_iter()
             .find(|f| f.id == font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
         
         Ok(FontFamily {
             id: font.id,
@@ -83,9 +92,12 @@ impl FontProviderTrait for GitHubFontsProvider {
                     style: FontStyle::Normal,
                     file_url: font.download_url.clone(),
                     file_format: "ttf".to_string(),
+                    axes: Vec::new(),
                 },
             ],
             license: font.license,
+            license_source: font.license_source,
+            license_confidence: font.license_confidence,
             designer: None,
             description: None,
             preview_url: font.preview_url,
@@ -94,6 +106,7 @@ impl FontProviderTrait for GitHubFontsProvider {
             subsets: vec!["latin".to_string()],
             popularity: None,
             last_modified: None,
+            date_added: None,
         })
     }
     
@@ -101,9 +114,9 @@ impl FontProviderTrait for GitHubFontsProvider {
         let fonts = self.list_all().await?;
         let font = fonts.into_iter()
             .find(|f| f.id == font_id)
-            .ok_or_else(|| anyhow::anyhow!("Font not found: {}", font_id))?;
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))?;
         
-        font.download_url.ok_or_else(|| anyhow::anyhow!("No download URL"))
+        font.download_url.ok_or_else(|| Error::Other(anyhow::anyhow!("No download URL for {}", font_id)))
     }
     
     async fn health_check(&self) -> Result<bool> {
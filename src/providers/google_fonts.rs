@@ -4,10 +4,10 @@
 //! API: https://fonts.google.com
 
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::http::HttpClient;
 use serde::Deserialize;
-use anyhow::Result;
-use crate::models::{Font, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, SearchQuery};
+use crate::error::Result;
+use crate::models::{Font, FontAxis, FontFamily, FontVariant, FontCategory, FontProvider, FontWeight, FontStyle, FontLicense, LicenseConfidence, LicenseSource, SearchQuery};
 use super::FontProviderTrait;
 
 /// Google Webfonts Helper API response
@@ -24,16 +24,26 @@ pub struct GwfhFont {
     pub popularity: Option<u32>,
     #[serde(rename = "defSubset")]
     pub default_subset: Option<String>,
+    /// Variation axes of variable families, as reported by the Google Fonts API
+    #[serde(default)]
+    pub axes: Vec<GwfhAxis>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GwfhAxis {
+    pub tag: String,
+    pub start: f32,
+    pub end: f32,
 }
 
 /// Google Fonts provider using the Google Webfonts Helper API
 pub struct GoogleFontsProvider {
-    client: Client,
+    client: HttpClient,
     api_url: String,
 }
 
 impl GoogleFontsProvider {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: HttpClient) -> Self {
         Self {
             client,
             api_url: "https://gwfh.mranftl.com/api/fonts".to_string(),
@@ -62,6 +72,44 @@ impl GoogleFontsProvider {
         (weight, style)
     }
     
+    /// Variable font files from the google/fonts repository, one per style
+    ///
+    /// Files are named after their axes, e.g. `ofl/inter/Inter-Italic[opsz,wght].ttf`.
+    /// Families outside the `ofl` directory (Apache, UFL) are not found there.
+    fn variable_variants(response: &GwfhFont) -> Vec<FontVariant> {
+        if response.axes.is_empty() {
+            return Vec::new();
+        }
+        let axes: Vec<FontAxis> = response.axes.iter()
+            .map(|a| {
+                let default = if a.tag == "wght" { 400f32.clamp(a.start, a.end) } else { a.start };
+                FontAxis::new(&a.tag, a.start, default, a.end)
+            })
+            .collect();
+        let mut tags: Vec<&str> = axes.iter().map(|a| a.tag.as_str()).collect();
+        tags.sort_unstable();
+        
+        let dir: String = response.family.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+        let file = response.family.replace(' ', "");
+        let default_weight = axes.iter().find(|a| a.tag == "wght").map_or(400, |a| a.default as u16);
+        let has_italic = response.variants.iter().any(|v| v.contains("italic"));
+        
+        [(FontStyle::Normal, ""), (FontStyle::Italic, "-Italic")]
+            .into_iter()
+            .filter(|(style, _)| *style == FontStyle::Normal || has_italic)
+            .map(|(style, suffix)| FontVariant {
+                weight: FontWeight::from_numeric(default_weight),
+                style,
+                file_url: Some(format!(
+                    "https://raw.githubusercontent.com/google/fonts/main/ofl/{}/{}{}[{}].ttf",
+                    dir, file, suffix, tags.join(",")
+                )),
+                file_format: "ttf".to_string(),
+                axes: axes.clone(),
+            })
+            .collect()
+    }
+    
     fn parse_category(category: &str) -> Option<FontCategory> {
         match category.to_lowercase().as_str() {
             "serif" => Some(FontCategory::Serif),
@@ -80,6 +128,10 @@ impl FontProviderTrait for GoogleFontsProvider {
         "Google Fonts"
     }
     
+    fn provider(&self) -> FontProvider {
+        FontProvider::GoogleFonts
+    }
+    
     fn base_url(&self) -> &str {
         "https://fonts.google.com"
     }
@@ -101,6 +153,7 @@ impl FontProviderTrait for GoogleFontsProvider {
             .get(&self.api_url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
@@ -112,7 +165,12 @@ impl FontProviderTrait for GoogleFontsProvider {
                 provider: FontProvider::GoogleFonts,
                 category: Self::parse_category(&f.category),
                 variant_count: f.variants.len(),
+                designer: None,
+                popularity: f.popularity,
+                score: None,
                 license: Some(FontLicense::OFL),
+                license_source: LicenseSource::Catalog,
+                license_confidence: LicenseConfidence::Reported,
                 preview_url: Some(format!(
                     "https://fonts.google.com/specimen/{}",
                     f.family.replace(' ', "+")
@@ -133,24 +191,28 @@ impl FontProviderTrait for GoogleFontsProvider {
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
         
-        let variants: Vec<FontVariant> = response.variants
+        // Google Webfonts Helper packages any variant in any of these formats
+        let mut variants: Vec<FontVariant> = response.variants
             .iter()
-            .map(|v| {
+            .flat_map(|v| {
                 let (weight, style) = Self::parse_variant(v);
-                FontVariant {
+                ["ttf", "woff", "woff2"].into_iter().map(move |format| FontVariant {
                     weight,
                     style,
                     file_url: Some(format!(
-                        "https://gwfh.mranftl.com/api/fonts/{}/{}?download=zip",
-                        font_id, v
+                        "https://gwfh.mranftl.com/api/fonts/{}?download=zip&subsets=latin&variants={}&formats={}",
+                        font_id, v, format
                     )),
-                    file_format: "ttf".to_string(),
-                }
+                    file_format: format.to_string(),
+                    axes: Vec::new(),
+                })
             })
             .collect();
+        variants.extend(Self::variable_variants(&response));
         
         Ok(FontFamily {
             id: response.id,
@@ -159,6 +221,8 @@ impl FontProviderTrait for GoogleFontsProvider {
             category: Self::parse_category(&response.category),
             variants,
             license: Some(FontLicense::OFL),
+            license_source: LicenseSource::Catalog,
+            license_confidence: LicenseConfidence::Reported,
             designer: None,
             description: None,
             preview_url: Some(format!(
@@ -173,6 +237,7 @@ impl FontProviderTrait for GoogleFontsProvider {
             subsets: response.subsets,
             popularity: response.popularity,
             last_modified: response.last_modified,
+            date_added: None,
         })
     }
     
@@ -0,0 +1,722 @@
+//! Google Fonts repository provider
+//!
+//! Reads the google/fonts repository layout from a local clone or tarball:
+//! `ofl/`, `apache/` and `ufl/` hold a directory per family with a
+//! `METADATA.pb` (protobuf text format) and a `DESCRIPTION.en_us.html`. The
+//! metadata carries the designer, license, axes, subsets, date added and
+//! file names of every family, and needs no network access. File URLs point
+//! at the upstream repository.
+
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use tokio::sync::OnceCell;
+
+use crate::archive;
+use crate::error::{Error, Result};
+use crate::models::{
+    Font, FontAxis, FontCategory, FontFamily, FontLicense, FontProvider, FontStyle, FontVariant, FontWeight,
+    LicenseConfidence, LicenseSource, SearchQuery,
+};
+use super::FontProviderTrait;
+
+/// Raw files of the upstream repository
+const RAW_URL: &str = "https://raw.githubusercontent.com/google/fonts/main";
+
+/// Top-level directories, one per license
+const LICENSE_DIRS: &[&str] = &["ofl", "apache", "ufl"];
+
+const METADATA_FILE: &str = "METADATA.pb";
+const DESCRIPTION_FILE: &str = "DESCRIPTION.en_us.html";
+
+/// A family's `METADATA.pb`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FamilyMetadata {
+    pub name: String,
+    pub designer: Option<String>,
+    /// `OFL`, `APACHE2` or `UFL`
+    pub license: Option<String>,
+    /// `SANS_SERIF`, `DISPLAY`, ...; the first is the primary one
+    pub categories: Vec<String>,
+    pub date_added: Option<String>,
+    pub subsets: Vec<String>,
+    pub fonts: Vec<MetadataFont>,
+    pub axes: Vec<FontAxis>,
+}
+
+/// A `fonts { ... }` entry: one file of the family
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataFont {
+    pub name: String,
+    /// `normal` or `italic`
+    pub style: String,
+    pub weight: u16,
+    /// `Roboto[wdth,wght].ttf`
+    pub filename: String,
+    pub post_script_name: Option<String>,
+    pub full_name: Option<String>,
+    pub copyright: Option<String>,
+}
+
+/// A family directory of the repository
+#[derive(Debug, Clone)]
+pub struct RepoFamily {
+    /// `ofl/roboto`
+    pub path: String,
+    pub metadata: FamilyMetadata,
+    /// Text of `DESCRIPTION.en_us.html`, without markup
+    pub description: Option<String>,
+}
+
+impl RepoFamily {
+    /// The directory name, used as the font id (`roboto`)
+    pub fn id(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    fn license(&self) -> Option<FontLicense> {
+        let license = self.metadata.license.as_deref().or_else(|| self.path.split('/').next())?;
+        Some(match license.to_uppercase().as_str() {
+            "UFL" => FontLicense::Custom("Ubuntu Font License 1.0".to_string()),
+            _ => FontLicense::from_identifier(license),
+        })
+    }
+
+    fn category(&self) -> Option<FontCategory> {
+        match self.metadata.categories.first()?.as_str() {
+            "SANS_SERIF" => Some(FontCategory::SansSerif),
+            "SERIF" => Some(FontCategory::Serif),
+            "DISPLAY" => Some(FontCategory::Display),
+            "HANDWRITING" => Some(FontCategory::Handwriting),
+            "MONOSPACE" => Some(FontCategory::Monospace),
+            _ => None,
+        }
+    }
+
+    fn file_url(&self, filename: &str) -> String {
+        format!("{}/{}/{}", RAW_URL, self.path, filename)
+    }
+
+    pub fn to_font(&self) -> Font {
+        Font {
+            id: self.id().to_string(),
+            name: self.metadata.name.clone(),
+            provider: FontProvider::GoogleFontsRepo,
+            category: self.category(),
+            variant_count: self.metadata.fonts.len(),
+            designer: self.metadata.designer.clone(),
+            popularity: None,
+            score: None,
+            license: self.license(),
+            license_source: LicenseSource::ProviderApi,
+            license_confidence: LicenseConfidence::Verified,
+            preview_url: Some(format!(
+                "https://fonts.google.com/specimen/{}",
+                self.metadata.name.replace(' ', "+")
+            )),
+            download_url: self.metadata.fonts.first().map(|f| self.file_url(&f.filename)),
+        }
+    }
+
+    pub fn to_family(&self) -> FontFamily {
+        let font = self.to_font();
+        let variants = self.metadata.fonts.iter()
+            .map(|file| FontVariant {
+                weight: FontWeight::from_numeric(file.weight),
+                style: if file.style == "italic" { FontStyle::Italic } else { FontStyle::Normal },
+                file_url: Some(self.file_url(&file.filename)),
+                file_format: archive::font_format(Path::new(&file.filename)).unwrap_or_else(|| "ttf".to_string()),
+                // Variable files are named after their axes: `Roboto[wdth,wght].ttf`
+                axes: if file.filename.contains('[') { self.metadata.axes.clone() } else { Vec::new() },
+            })
+            .collect();
+        FontFamily {
+            id: font.id,
+            name: font.name,
+            provider: font.provider,
+            category: font.category,
+            variants,
+            license: font.license,
+            license_source: font.license_source,
+            license_confidence: font.license_confidence,
+            designer: font.designer,
+            description: self.description.clone(),
+            preview_url: font.preview_url,
+            download_url: font.download_url,
+            languages: self.metadata.subsets.iter()
+                .filter(|s| s.as_str() != "menu")
+                .map(|s| subset_language(s))
+                .collect(),
+            subsets: self.metadata.subsets.clone(),
+            popularity: None,
+            last_modified: None,
+            date_added: self.metadata.date_added.clone(),
+        }
+    }
+}
+
+/// `Latin Ext` from the subset `latin-ext`
+fn subset_language(subset: &str) -> String {
+    subset.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Provider over a local copy of the google/fonts repository
+pub struct GoogleFontsRepoProvider {
+    path: PathBuf,
+    base_url: String,
+    families: OnceCell<Vec<RepoFamily>>,
+}
+
+impl GoogleFontsRepoProvider {
+    /// `path` is a clone of the repository or a `.tar`/`.tar.gz` of it
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            base_url: FontProvider::GoogleFontsRepo.base_url().to_string(),
+            families: OnceCell::new(),
+        }
+    }
+
+    /// Every family in the repository, read once
+    async fn families(&self) -> Result<&[RepoFamily]> {
+        let families = self.families
+            .get_or_try_init(|| async {
+                let path = self.path.clone();
+                tokio::task::spawn_blocking(move || load_repo(&path))
+                    .await
+                    .map_err(anyhow::Error::from)?
+            })
+            .await?;
+        Ok(families)
+    }
+
+    async fn family(&self, font_id: &str) -> Result<&RepoFamily> {
+        self.families().await?
+            .iter()
+            .find(|f| f.id() == font_id || f.path == font_id)
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))
+    }
+}
+
+/// Read every family from a repository clone or tarball
+///
+/// Families whose metadata does not parse are skipped with a warning.
+pub fn load_repo(path: &Path) -> Result<Vec<RepoFamily>> {
+    let files = if path.is_dir() {
+        read_dir_files(path)?
+    } else if path.is_file() {
+        read_tarball(path)?
+    } else {
+        return Err(Error::Other(anyhow::anyhow!("Google Fonts repository not found at {}", path.display())));
+    };
+
+    let mut families = Vec::new();
+    for (family_path, (metadata, description)) in files {
+        let Some(metadata) = metadata else { continue };
+        match parse_metadata(&metadata) {
+            Ok(metadata) => families.push(RepoFamily {
+                path: family_path,
+                metadata,
+                description: description.map(|html| html_text(&html)),
+            }),
+            Err(e) => tracing::warn!("Skipping {}/{}: {}", family_path, METADATA_FILE, e),
+        }
+    }
+    Ok(families)
+}
+
+/// Metadata and description text per family path (`ofl/roboto`)
+type RepoFiles = BTreeMap<String, (Option<String>, Option<String>)>;
+
+fn read_dir_files(root: &Path) -> Result<RepoFiles> {
+    let mut files = RepoFiles::new();
+    for license in LICENSE_DIRS {
+        let Ok(entries) = std::fs::read_dir(root.join(license)) else { continue };
+        for entry in entries {
+            let dir = entry?.path();
+            let Ok(metadata) = std::fs::read_to_string(dir.join(METADATA_FILE)) else { continue };
+            let description = std::fs::read_to_string(dir.join(DESCRIPTION_FILE)).ok();
+            let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            files.insert(format!("{}/{}", license, name), (Some(metadata), description));
+        }
+    }
+    Ok(files)
+}
+
+/// Family path and file name for repository paths such as
+/// `fonts-main/ofl/roboto/METADATA.pb`
+fn repo_file(path: &str) -> Option<(String, &str)> {
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    let [.., license, family, file] = parts.as_slice() else {
+        return None;
+    };
+    (LICENSE_DIRS.contains(license) && (*file == METADATA_FILE || *file == DESCRIPTION_FILE))
+        .then(|| (format!("{}/{}", license, family), *file))
+}
+
+/// Read the metadata and description files out of a (gzipped) tarball
+fn read_tarball(path: &Path) -> Result<RepoFiles> {
+    let file = BufReader::new(std::fs::File::open(path)?);
+    let name = path.to_string_lossy().to_lowercase();
+    let mut reader: Box<dyn Read> = if name.ends_with(".gz") || name.ends_with(".tgz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    let mut files = RepoFiles::new();
+    let mut header = [0u8; 512];
+    // Set by pax (`x`) and GNU long name (`L`) entries for the entry after them
+    let mut long_name: Option<String> = None;
+    loop {
+        if !read_block(&mut reader, &mut header)? || header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let size = octal(&header[124..136]);
+        let padded = size.div_ceil(512) * 512;
+        let mut name = cstr(&header[0..100]);
+        if &header[257..262] == b"ustar" {
+            let prefix = cstr(&header[345..500]);
+            if !prefix.is_empty() {
+                name = format!("{}/{}", prefix, name);
+            }
+        }
+
+        match header[156] {
+            b'x' | b'L' => {
+                let data = read_data(&mut reader, size, padded)?;
+                long_name = if header[156] == b'L' {
+                    Some(cstr(&data))
+                } else {
+                    pax_path(&data)
+                };
+            }
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or(name);
+                match repo_file(&name) {
+                    Some((family, file)) => {
+                        let text = String::from_utf8_lossy(&read_data(&mut reader, size, padded)?).into_owned();
+                        let entry = files.entry(family).or_default();
+                        if file == METADATA_FILE {
+                            entry.0 = Some(text);
+                        } else {
+                            entry.1 = Some(text);
+                        }
+                    }
+                    None => skip(&mut reader, padded)?,
+                }
+            }
+            _ => {
+                long_name = None;
+                skip(&mut reader, padded)?;
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Fill `block`; `false` at a clean end of the stream
+fn read_block(reader: &mut dyn Read, block: &mut [u8; 512]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < block.len() {
+        match reader.read(&mut block[filled..])? {
+            0 if filled == 0 => return Ok(false),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => filled += n,
+        }
+    }
+    Ok(true)
+}
+
+fn read_data(reader: &mut dyn Read, size: u64, padded: u64) -> io::Result<Vec<u8>> {
+    let mut data = vec![0u8; padded as usize];
+    reader.read_exact(&mut data)?;
+    data.truncate(size as usize);
+    Ok(data)
+}
+
+fn skip(reader: &mut dyn Read, len: u64) -> io::Result<()> {
+    let copied = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if copied < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn octal(bytes: &[u8]) -> u64 {
+    let text = cstr(bytes);
+    u64::from_str_radix(text.trim(), 8).unwrap_or(0)
+}
+
+/// The `path` record of pax extended header data (`27 path=some/long/name\n`)
+fn pax_path(data: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .find_map(|record| record.split_once(' ')?.1.strip_prefix("path=").map(str::to_string))
+}
+
+/// Text of an HTML fragment with whitespace collapsed
+fn html_text(html: &str) -> String {
+    scraper::Html::parse_fragment(html)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a `METADATA.pb` file
+pub fn parse_metadata(text: &str) -> std::result::Result<FamilyMetadata, String> {
+    let message = TextFormat::new(text).message(false)?;
+    let name = message.scalar("name").ok_or("missing family name")?.to_string();
+    let fonts = message.messages("fonts")
+        .map(|font| MetadataFont {
+            name: font.scalar("name").unwrap_or(&name).to_string(),
+            style: font.scalar("style").unwrap_or("normal").to_string(),
+            weight: font.scalar("weight").and_then(|w| w.parse().ok()).unwrap_or(400),
+            filename: font.scalar("filename").unwrap_or_default().to_string(),
+            post_script_name: font.scalar("post_script_name").map(str::to_string),
+            full_name: font.scalar("full_name").map(str::to_string),
+            copyright: font.scalar("copyright").map(str::to_string),
+        })
+        .filter(|font| !font.filename.is_empty())
+        .collect();
+    let axes = message.messages("axes")
+        .filter_map(|axis| {
+            let tag = axis.scalar("tag")?;
+            let min: f32 = axis.scalar("min_value")?.parse().ok()?;
+            let max: f32 = axis.scalar("max_value")?.parse().ok()?;
+            // METADATA.pb has no default; use the registered one where the axis has it
+            let default: f32 = match tag {
+                "wght" => 400.0,
+                "wdth" => 100.0,
+                _ => 0.0,
+            };
+            Some(FontAxis::new(tag, min, default.max(min).min(max), max))
+        })
+        .collect();
+
+    Ok(FamilyMetadata {
+        designer: message.scalar("designer").map(str::to_string),
+        license: message.scalar("license").map(str::to_string),
+        categories: message.scalars("category").map(str::to_string).collect(),
+        date_added: message.scalar("date_added").map(str::to_string),
+        subsets: message.scalars("subsets").map(str::to_string).collect(),
+        fonts,
+        axes,
+        name,
+    })
+}
+
+/// A protobuf text-format message: fields in file order
+#[derive(Debug, Default)]
+struct Message {
+    fields: Vec<(String, Field)>,
+}
+
+#[derive(Debug)]
+enum Field {
+    Scalar(String),
+    Message(Message),
+}
+
+impl Message {
+    fn scalar(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find_map(|(n, field)| match field {
+            Field::Scalar(value) if n == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    fn scalars<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.fields.iter().filter_map(move |(n, field)| match field {
+            Field::Scalar(value) if n == name => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    fn messages<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Message> + 'a {
+        self.fields.iter().filter_map(move |(n, field)| match field {
+            Field::Message(message) if n == name => Some(message),
+            _ => None,
+        })
+    }
+}
+
+/// Recursive-descent reader for the protobuf text format
+struct TextFormat<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Colon,
+    Str(String),
+    Word(String),
+}
+
+impl<'a> TextFormat<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { chars: text.chars().peekable() }
+    }
+
+    /// Fields up to the end of the input, or up to `}` when `nested`
+    fn message(&mut self, nested: bool) -> std::result::Result<Message, String> {
+        let mut message = Message::default();
+        loop {
+            let name = match self.token()? {
+                None if nested => return Err("unexpected end of input".to_string()),
+                None => return Ok(message),
+                Some(Token::Close) if nested => return Ok(message),
+                Some(Token::Word(name)) => name,
+                Some(token) => return Err(format!("expected a field name, found {:?}", token)),
+            };
+            let mut token = self.token()?;
+            if token == Some(Token::Colon) {
+                token = self.token()?;
+            }
+            let field = match token {
+                Some(Token::Open) => Field::Message(self.message(true)?),
+                Some(Token::Str(mut value)) => {
+                    // Adjacent strings are concatenated
+                    while self.peek_char() == Some('"') || self.peek_char() == Some('\'') {
+                        match self.token()? {
+                            Some(Token::Str(more)) => value.push_str(&more),
+                            _ => unreachable!("a quote starts a string"),
+                        }
+                    }
+                    Field::Scalar(value)
+                }
+                Some(Token::Word(value)) => Field::Scalar(value),
+                other => return Err(format!("expected a value for '{}', found {:?}", name, other)),
+            };
+            message.fields.push((name, field));
+        }
+    }
+
+    /// Next character that is not whitespace or part of a `#` comment
+    fn peek_char(&mut self) -> Option<char> {
+        loop {
+            match self.chars.peek()? {
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                '#' => {
+                    while self.chars.next().is_some_and(|c| c != '\n') {}
+                }
+                &c => return Some(c),
+            }
+        }
+    }
+
+    fn token(&mut self) -> std::result::Result<Option<Token>, String> {
+        let Some(c) = self.peek_char() else {
+            return Ok(None);
+        };
+        self.chars.next();
+        let token = match c {
+            '{' | '<' => Token::Open,
+            '}' | '>' => Token::Close,
+            ':' => Token::Colon,
+            '"' | '\'' => Token::Str(self.string(c)?),
+            _ => {
+                let mut word = c.to_string();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || "{}<>:#\"'".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    self.chars.next();
+                }
+                Token::Word(word)
+            }
+        };
+        Ok(Some(token))
+    }
+
+    /// A quoted string; octal escapes are UTF-8 bytes
+    fn string(&mut self, quote: char) -> std::result::Result<String, String> {
+        let mut bytes = Vec::new();
+        loop {
+            let c = self.chars.next().ok_or("unterminated string")?;
+            match c {
+                c if c == quote => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+                '\\' => {
+                    let escaped = self.chars.next().ok_or("unterminated string")?;
+                    match escaped {
+                        'n' => bytes.push(b'\n'),
+                        't' => bytes.push(b'\t'),
+                        'r' => bytes.push(b'\r'),
+                        '0'..='7' => {
+                            let mut value = escaped.to_digit(8).unwrap_or(0);
+                            for _ in 0..2 {
+                                match self.chars.peek().and_then(|c| c.to_digit(8)) {
+                                    Some(digit) => {
+                                        value = value * 8 + digit;
+                                        self.chars.next();
+                                    }
+                                    None => break,
+                                }
+                            }
+                            bytes.push(value as u8);
+                        }
+                        other => {
+                            let mut buf = [0u8; 4];
+                            bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+                        }
+                    }
+                }
+                other => {
+                    let mut buf = [0u8; 4];
+                    bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FontProviderTrait for GoogleFontsRepoProvider {
+    fn name(&self) -> &str { "Google Fonts Repository" }
+    fn provider(&self) -> FontProvider { FontProvider::GoogleFontsRepo }
+    fn base_url(&self) -> &str { &self.base_url }
+    fn is_local(&self) -> bool { true }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Font>> {
+        let query = query.query.to_lowercase();
+        Ok(self.families().await?
+            .iter()
+            .filter(|f| f.metadata.name.to_lowercase().contains(&query))
+            .map(RepoFamily::to_font)
+            .collect())
+    }
+
+    async fn list_all(&self) -> Result<Vec<Font>> {
+        Ok(self.families().await?.iter().map(RepoFamily::to_font).collect())
+    }
+
+    async fn get_font_family(&self, font_id: &str) -> Result<FontFamily> {
+        Ok(self.family(font_id).await?.to_family())
+    }
+
+    async fn get_download_url(&self, font_id: &str) -> Result<String> {
+        self.family(font_id).await?
+            .to_font()
+            .download_url
+            .ok_or_else(|| Error::font_not_found(self.name(), font_id))
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self.path.exists())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata_fixture() {
+        let metadata = parse_metadata(include_str!("fixtures/google_fonts_METADATA.pb")).unwrap();
+        assert_eq!(metadata.name, "Roboto Flex");
+        assert_eq!(metadata.designer.as_deref(), Some("Font Bureau, David Berlow"));
+        assert_eq!(metadata.date_added.as_deref(), Some("2022-03-07"));
+        assert_eq!(metadata.subsets, vec!["cyrillic", "latin", "latin-ext", "menu"]);
+        assert_eq!(metadata.fonts.len(), 1);
+        assert_eq!(metadata.fonts[0].filename, "RobotoFlex[GRAD,wdth,wght].ttf");
+        assert_eq!(metadata.fonts[0].copyright.as_deref(), Some("Copyright 2017 The Roboto Flex Project Authors (https://github.com/googlefonts/roboto-flex) \u{a9}"));
+        assert_eq!(metadata.axes.len(), 3);
+        assert_eq!(metadata.axes[2], FontAxis::new("wght", 100.0, 400.0, 1000.0));
+
+        let family = RepoFamily { path: "ofl/robotoflex".to_string(), metadata, description: None }.to_family();
+        assert_eq!(family.id, "robotoflex");
+        assert_eq!(family.license, Some(FontLicense::OFL));
+        assert_eq!(family.category, Some(FontCategory::SansSerif));
+        assert_eq!(family.languages, vec!["Cyrillic", "Latin", "Latin Ext"]);
+        assert!(family.is_variable());
+        assert_eq!(
+            family.variants[0].file_url.as_deref(),
+            Some("https://raw.githubusercontent.com/google/fonts/main/ofl/robotoflex/RobotoFlex[GRAD,wdth,wght].ttf")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_works_offline_without_snapshot() {
+        use crate::cache::{CachePolicy, CatalogCache};
+        use crate::providers::ProviderRegistry;
+        use std::sync::Arc;
+
+        let root = std::env::temp_dir().join(format!("dx-font-gfrepo-offline-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("ofl/robotoflex")).unwrap();
+        std::fs::write(root.join("ofl/robotoflex/METADATA.pb"), include_str!("fixtures/google_fonts_METADATA.pb")).unwrap();
+
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(GoogleFontsRepoProvider::new(root.clone())));
+        let cache = CatalogCache::new(root.join("cache"), std::time::Duration::from_secs(60))
+            .with_policy(CachePolicy::Offline);
+        let registry = registry.with_cache(cache);
+
+        let listed = registry.list_all_concurrent().await.unwrap();
+        let family = registry.get(&FontProvider::GoogleFontsRepo).unwrap().get_font_family("robotoflex").await;
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(listed.fonts.len(), 1);
+        assert_eq!(family.unwrap().name, "Roboto Flex");
+    }
+
+    #[test]
+    fn test_load_tarball() {
+        fn entry(tar: &mut Vec<u8>, name: &str, typeflag: u8, data: &[u8]) {
+            let mut header = [0u8; 512];
+            header[..name.len().min(100)].copy_from_slice(&name.as_bytes()[..name.len().min(100)]);
+            header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+            header[156] = typeflag;
+            header[257..263].copy_from_slice(b"ustar\0");
+            tar.extend_from_slice(&header);
+            tar.extend_from_slice(data);
+            tar.resize(tar.len().div_ceil(512) * 512, 0);
+        }
+
+        let long = format!("fonts-main/ofl/{}/METADATA.pb", "a".repeat(100));
+        let pax = format!("{} path={}\n", long.len() + 7 + 3, long);
+        let mut tar = Vec::new();
+        entry(&mut tar, "fonts-main/ofl/abeezee/METADATA.pb", b'0', b"name: \"ABeeZee\"\nlicense: \"OFL\"\ncategory: \"SANS_SERIF\"\n");
+        entry(&mut tar, "fonts-main/ofl/abeezee/DESCRIPTION.en_us.html", b'0', b"<p>A <b>children's</b> font</p>");
+        entry(&mut tar, "fonts-main/ofl/abeezee/ABeeZee-Regular.ttf", b'0', &[0u8; 700]);
+        entry(&mut tar, "PaxHeaders/long", b'x', pax.as_bytes());
+        entry(&mut tar, "truncated", b'0', b"name: \"Long Name\"\n");
+        entry(&mut tar, "fonts-main/README.md", b'0', b"# Fonts");
+        tar.extend_from_slice(&[0u8; 1024]);
+
+        let path = std::env::temp_dir().join(format!("dx-font-gfrepo-{}.tar.gz", std::process::id()));
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        io::Write::write_all(&mut encoder, &tar).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let families = load_repo(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(families.len(), 2);
+        assert_eq!(families[0].path, format!("ofl/{}", "a".repeat(100)));
+        assert_eq!(families[0].metadata.name, "Long Name");
+        assert_eq!(families[1].id(), "abeezee");
+        assert_eq!(families[1].description.as_deref(), Some("A children's font"));
+    }
+}
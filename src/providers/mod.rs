@@ -4,6 +4,7 @@
 //! Optimized for performance with concurrent fetching and connection pooling.
 
 pub mod google_fonts;
+pub mod google_fonts_repo;
 pub mod bunny_fonts;
 pub mod fontsource;
 pub mod fontshare;
@@ -13,11 +14,18 @@ pub mod dafont;
 pub mod fontspace;
 pub mod fonts1001;
 pub mod fontsquirrel;
+pub(crate) mod scrape;
 
 use async_trait::async_trait;
-use crate::models::{FontFamily, Font, SearchQuery, SearchResults};
-use anyhow::Result;
+use crate::cache::{CachePolicy, CachedProvider, CatalogCache};
+use crate::config::{Config, HttpConfig};
+use crate::error::{Error, ProviderError, Result};
+use crate::http::HttpClient;
+use crate::models::{FontFamily, Font, FontProvider, ProviderOutcome, SearchQuery, SearchResults};
+use crate::stream::{self, ProviderBatch};
+use crate::{dedup, pagination, ranking};
 use futures::future::join_all;
+use futures::stream::{BoxStream, FuturesUnordered, StreamExt};
 use tokio::time::{timeout, Duration, Instant};
 use std::sync::Arc;
 
@@ -30,6 +38,27 @@ pub trait FontProviderTrait: Send + Sync {
     /// Get the base URL for this provider
     fn base_url(&self) -> &str;
     
+    /// The provider this implementation serves fonts for
+    fn provider(&self) -> FontProvider {
+        FontProvider::Custom(self.name().to_string())
+    }
+    
+    /// Whether `list_all` returns the provider's whole catalog
+    ///
+    /// Scraped sites only list a top or category slice; searches on those
+    /// go through [`Self::search`] instead of ranking the listing.
+    fn has_full_catalog(&self) -> bool {
+        true
+    }
+    
+    /// Whether the provider reads local files only
+    ///
+    /// Local providers need no network, so they are never put behind the
+    /// catalog cache and keep working offline without a snapshot.
+    fn is_local(&self) -> bool {
+        false
+    }
+    
     /// Search for fonts matching the query
     async fn search(&self, query: &SearchQuery) -> Result<Vec<Font>>;
     
@@ -47,11 +76,17 @@ pub trait FontProviderTrait: Send + Sync {
 }
 
 /// Create an HTTP client with optimized settings for performance
-pub fn create_http_client() -> Result<reqwest::Client> {
+pub fn create_http_client() -> Result<HttpClient> {
+    create_http_client_with(&Config::default())
+}
+
+/// Create an HTTP client using the timeouts, user agent and retry/rate limit
+/// settings from `config`
+pub fn create_http_client_with(config: &Config) -> Result<HttpClient> {
     let client = reqwest::Client::builder()
-        .user_agent(format!("dx-font/{}", env!("CARGO_PKG_VERSION")))
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10))
+        .user_agent(config.user_agent.as_str())
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
         // Connection pooling for better performance
         .pool_max_idle_per_host(10)
         .pool_idle_timeout(Duration::from_secs(90))
@@ -60,37 +95,67 @@ pub fn create_http_client() -> Result<reqwest::Client> {
         .brotli(true)
         .deflate(true)
         .build()?;
-    Ok(client)
+    Ok(HttpClient::new(client, config.http.clone()))
 }
 
 /// Create a fast HTTP client with shorter timeouts for health checks
-pub fn create_fast_http_client() -> Result<reqwest::Client> {
+pub fn create_fast_http_client() -> Result<HttpClient> {
+    create_fast_http_client_with(&Config::default())
+}
+
+/// Create a fast HTTP client using the user agent from `config`
+///
+/// Timeouts are capped at 5s/3s but never exceed the configured ones, and
+/// failed requests are not retried.
+pub fn create_fast_http_client_with(config: &Config) -> Result<HttpClient> {
     let client = reqwest::Client::builder()
-        .user_agent(format!("dx-font/{}", env!("CARGO_PKG_VERSION")))
-        .timeout(Duration::from_secs(5))
-        .connect_timeout(Duration::from_secs(3))
+        .user_agent(config.user_agent.as_str())
+        .timeout(Duration::from_secs(config.timeout_seconds.min(5)))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_seconds.min(3)))
         .pool_max_idle_per_host(5)
         .build()?;
-    Ok(client)
+    let http = HttpConfig { max_retries: 0, ..config.http.clone() };
+    Ok(HttpClient::new(client, http))
 }
 
+/// How long a search waits for any one provider
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long a full listing waits for any one provider
+const LIST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a health check waits for any one provider
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Registry of all available font providers
 pub struct ProviderRegistry {
     providers: Vec<Arc<dyn FontProviderTrait>>,
+    offline: bool,
+    cache: Option<Arc<CatalogCache>>,
+    priority: Vec<FontProvider>,
 }
 
 impl ProviderRegistry {
     pub fn new() -> Self {
         Self {
             providers: Vec::new(),
+            offline: false,
+            cache: None,
+            priority: dedup::default_priority(),
         }
     }
     
     pub fn with_defaults() -> Result<Self> {
-        let client = create_http_client()?;
-        let mut registry = Self::new();
+        Self::with_config(&Config::default())
+    }
+    
+    /// Create a registry with all default providers sharing an HTTP client built from `config`
+    pub fn with_config(config: &Config) -> Result<Self> {
+        let client = create_http_client_with(config)?;
+        let mut registry = Self::new().with_priority(config.priority_order());
         
         // Add all providers for maximum font coverage (50k+ fonts)
+        if let Some(repo) = &config.google_fonts_repo {
+            registry.register(Arc::new(google_fonts_repo::GoogleFontsRepoProvider::new(repo.clone())));
+        }
         registry.register(Arc::new(google_fonts::GoogleFontsProvider::new(client.clone())));
         registry.register(Arc::new(bunny_fonts::BunnyFontsProvider::new(client.clone())));
         registry.register(Arc::new(fontsource::FontsourceProvider::new(client.clone())));
@@ -109,125 +174,229 @@ impl ProviderRegistry {
         self.providers.push(provider);
     }
     
+    /// Put every registered network provider behind an on-disk catalog cache
+    ///
+    /// With [`CachePolicy::Offline`] the registry runs purely from the cached
+    /// snapshot and reports missing data as an error instead of an empty result.
+    /// Local providers are left as they are and read their files directly.
+    pub fn with_cache(self, cache: CatalogCache) -> Self {
+        let offline = cache.policy() == CachePolicy::Offline;
+        let cache = Arc::new(cache);
+        let providers = self.providers
+            .into_iter()
+            .map(|p| match p.is_local() {
+                true => p,
+                false => Arc::new(CachedProvider::new(p, Arc::clone(&cache))) as Arc<dyn FontProviderTrait>,
+            })
+            .collect();
+        Self { providers, offline, cache: Some(cache), ..self }
+    }
+    
+    /// Set the provider order used to pick a family's preferred source
+    pub fn with_priority(mut self, priority: Vec<FontProvider>) -> Self {
+        self.priority = priority;
+        self
+    }
+    
+    /// Provider order used to pick a family's preferred source
+    pub fn priority(&self) -> &[FontProvider] {
+        &self.priority
+    }
+    
+    /// Let background catalog refreshes finish, waiting at most `limit`
+    pub async fn wait_for_refreshes(&self, limit: Duration) {
+        if let Some(cache) = &self.cache {
+            cache.wait_for_refreshes(limit).await;
+        }
+    }
+    
+    /// Whether this registry only serves the offline snapshot
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+    
     pub fn providers(&self) -> &[Arc<dyn FontProviderTrait>] {
         &self.providers
     }
     
+    /// Find the registered implementation for a provider
+    pub fn get(&self, provider: &FontProvider) -> Option<&Arc<dyn FontProviderTrait>> {
+        self.providers.iter().find(|p| &p.provider() == provider)
+    }
+    
     /// Search all providers concurrently for maximum speed
+    ///
+    /// Only providers selected by the query's provider filters are searched,
+    /// and results are narrowed to the requested categories.
     pub async fn search_all(&self, query: &SearchQuery) -> Result<SearchResults> {
         let start = Instant::now();
-        let providers_searched: Vec<String> = self.providers.iter()
+        let selected: Vec<&Arc<dyn FontProviderTrait>> = self.providers.iter()
+            .filter(|p| query.selects_provider(&p.provider()))
+            .collect();
+        let providers_searched: Vec<String> = selected.iter()
             .map(|p| p.name().to_string())
             .collect();
         
-        // Create search futures for all providers
-        let search_futures: Vec<_> = self.providers.iter()
-            .map(|provider| {
-                let provider = Arc::clone(provider);
-                let query = query.clone();
-                async move {
-                    // Add timeout for each provider to prevent slow providers from blocking
-                    match timeout(Duration::from_secs(15), provider.search(&query)).await {
-                        Ok(Ok(fonts)) => {
-                            tracing::debug!("Provider {} returned {} fonts", provider.name(), fonts.len());
-                            fonts
-                        }
-                        Ok(Err(e)) => {
-                            tracing::warn!("Error searching {}: {}", provider.name(), e);
-                            Vec::new()
-                        }
-                        Err(_) => {
-                            tracing::warn!("Timeout searching {}", provider.name());
-                            Vec::new()
-                        }
-                    }
-                }
-            })
+        // Fetch whole catalogs so ranking can see fuzzy matches the
+        // providers' own substring search would drop; providers without a
+        // full catalog search on their side
+        let search_futures: Vec<_> = selected.into_iter()
+            .map(|provider| stream::fetch_batch(Arc::clone(provider), Some(query.clone()), SEARCH_TIMEOUT))
             .collect();
         
         // Execute all searches concurrently
-        let results = join_all(search_futures).await;
+        let batches = join_all(search_futures).await;
         
         // Flatten results from all providers
-        let mut all_fonts: Vec<Font> = results.into_iter().flatten().collect();
-        
-        let total = all_fonts.len();
-        
-        // Apply limit if specified
-        if let Some(limit) = query.limit {
-            all_fonts.truncate(limit);
-        }
+        let (mut all_fonts, outcomes) = self.collect_results(batches)?;
+        all_fonts.retain(|f| query.matches_category(f.category.as_ref()) && query.matches_license(f));
+        let all_fonts = ranking::rank(all_fonts, &query.query);
         
         let elapsed = start.elapsed();
-        tracing::info!("Search completed in {:?}, found {} fonts", elapsed, total);
+        tracing::info!("Search completed in {:?}, found {} fonts", elapsed, all_fonts.len());
         
-        Ok(SearchResults {
-            fonts: all_fonts,
-            total,
-            query: query.query.clone(),
-            providers_searched,
-        })
+        self.page(all_fonts, query, providers_searched, outcomes)
+    }
+    
+    /// Search all selected providers, yielding each provider's matches as soon as it answers
+    ///
+    /// Every batch is filtered and ranked on its own; slow or failing
+    /// providers show up as batches with a timeout or error status instead of
+    /// holding back the others. Paging and deduplication do not apply.
+    pub fn search_stream(&self, query: &SearchQuery) -> BoxStream<'static, ProviderBatch> {
+        let pending: FuturesUnordered<_> = self.providers.iter()
+            .filter(|p| query.selects_provider(&p.provider()))
+            .map(|provider| stream::fetch_batch(Arc::clone(provider), Some(query.clone()), SEARCH_TIMEOUT))
+            .collect();
+        
+        let query = query.clone();
+        let ranker = ranking::Ranker::new(&query.query);
+        pending
+            .map(move |mut batch| {
+                batch.fonts.retain(|f| query.matches_category(f.category.as_ref()) && query.matches_license(f));
+                batch.fonts = ranker.rank(std::mem::take(&mut batch.fonts));
+                batch
+            })
+            .boxed()
     }
     
-    /// List all fonts from all providers concurrently
+    /// List all fonts from all providers concurrently, sorted by name
     pub async fn list_all_concurrent(&self) -> Result<SearchResults> {
+        self.list_page(&SearchQuery::default()).await
+    }
+    
+    /// List one page of the catalog, sorted by name
+    ///
+    /// The query's text is ignored; its provider and category filters,
+    /// `dedupe` and paging fields apply as in [`Self::search_all`].
+    pub async fn list_page(&self, query: &SearchQuery) -> Result<SearchResults> {
         let start = Instant::now();
-        let providers_searched: Vec<String> = self.providers.iter()
+        let selected: Vec<&Arc<dyn FontProviderTrait>> = self.providers.iter()
+            .filter(|p| query.selects_provider(&p.provider()))
+            .collect();
+        let providers_searched: Vec<String> = selected.iter()
             .map(|p| p.name().to_string())
             .collect();
         
-        // Create list futures for all providers
-        let list_futures: Vec<_> = self.providers.iter()
-            .map(|provider| {
-                let provider = Arc::clone(provider);
-                async move {
-                    match timeout(Duration::from_secs(30), provider.list_all()).await {
-                        Ok(Ok(fonts)) => {
-                            tracing::debug!("Provider {} listed {} fonts", provider.name(), fonts.len());
-                            fonts
-                        }
-                        Ok(Err(e)) => {
-                            tracing::warn!("Error listing from {}: {}", provider.name(), e);
-                            Vec::new()
-                        }
-                        Err(_) => {
-                            tracing::warn!("Timeout listing from {}", provider.name());
-                            Vec::new()
-                        }
-                    }
-                }
-            })
+        // Create list futures for the selected providers
+        let list_futures: Vec<_> = selected.into_iter()
+            .map(|provider| stream::fetch_batch(Arc::clone(provider), None, LIST_TIMEOUT))
             .collect();
         
         // Execute all lists concurrently
-        let results = join_all(list_futures).await;
-        let all_fonts: Vec<Font> = results.into_iter().flatten().collect();
-        let total = all_fonts.len();
+        let batches = join_all(list_futures).await;
+        let (mut all_fonts, outcomes) = self.collect_results(batches)?;
+        all_fonts.retain(|f| query.matches_category(f.category.as_ref()) && query.matches_license(f));
+        all_fonts.sort_by(ranking::by_name);
         
         let elapsed = start.elapsed();
-        tracing::info!("List all completed in {:?}, found {} fonts", elapsed, total);
+        tracing::info!("List all completed in {:?}, found {} fonts", elapsed, all_fonts.len());
+        
+        self.page(all_fonts, query, providers_searched, outcomes)
+    }
+    
+    /// Deduplicate if requested and cut the requested page out of sorted fonts
+    fn page(
+        &self,
+        fonts: Vec<Font>,
+        query: &SearchQuery,
+        providers_searched: Vec<String>,
+        outcomes: Vec<ProviderOutcome>,
+    ) -> Result<SearchResults> {
+        let offset = pagination::start_offset(query)?;
+        
+        let (fonts, families, total, next) = if query.dedupe {
+            let families = dedup::merge(&fonts, &self.priority);
+            let total = families.len();
+            let (families, next) = pagination::paginate(families, offset, query.limit);
+            (Vec::new(), families, total, next)
+        } else {
+            let total = fonts.len();
+            let (fonts, next) = pagination::paginate(fonts, offset, query.limit);
+            (fonts, Vec::new(), total, next)
+        };
         
         Ok(SearchResults {
-            fonts: all_fonts,
+            fonts,
             total,
-            query: String::new(),
+            query: query.query.clone(),
             providers_searched,
+            offset,
+            next_cursor: next.map(|n| pagination::Cursor::new(n, query).encode()),
+            families,
+            outcomes,
         })
     }
     
+    /// Merge per-provider batches and report how each provider fared
+    ///
+    /// Online, failed providers are skipped so one bad source doesn't break a
+    /// search; their failures are left in the outcomes for the caller. Offline,
+    /// any failure means the snapshot is incomplete and is reported as an error.
+    fn collect_results(&self, batches: Vec<ProviderBatch>) -> Result<(Vec<Font>, Vec<ProviderOutcome>)> {
+        let outcomes: Vec<ProviderOutcome> = batches.iter().map(ProviderBatch::outcome).collect();
+        
+        if self.offline {
+            let missing: Vec<String> = batches.iter()
+                .filter_map(|b| b.error().map(|e| format!("{}: {}", b.provider, e)))
+                .collect();
+            if !missing.is_empty() {
+                return Err(Error::Offline(format!(
+                    "Offline catalog is incomplete:\n  {}\nRun `dx-font cache update` while online to refresh the snapshot",
+                    missing.join("\n  ")
+                )));
+            }
+        }
+        
+        let fonts = batches.into_iter().flat_map(|b| b.fonts).collect();
+        Ok((fonts, outcomes))
+    }
+    
     /// Check health of all providers concurrently
-    pub async fn health_check_all(&self) -> Vec<(String, bool, Duration)> {
+    ///
+    /// A provider is healthy when its outcome has no error.
+    pub async fn health_check_all(&self) -> Vec<ProviderOutcome> {
         let health_futures: Vec<_> = self.providers.iter()
             .map(|provider| {
                 let provider = Arc::clone(provider);
                 async move {
                     let start = Instant::now();
-                    let is_healthy = match timeout(Duration::from_secs(5), provider.health_check()).await {
-                        Ok(Ok(healthy)) => healthy,
-                        _ => false,
+                    let error = match timeout(HEALTH_TIMEOUT, provider.health_check()).await {
+                        Ok(Ok(true)) => None,
+                        Ok(Ok(false)) => Some(ProviderError::Other {
+                            message: "health check failed".to_string(),
+                        }),
+                        Ok(Err(e)) => Some(ProviderError::from(&e)),
+                        Err(_) => Some(ProviderError::Timeout {
+                            message: format!("no answer within {}s", HEALTH_TIMEOUT.as_secs()),
+                        }),
                     };
-                    let elapsed = start.elapsed();
-                    (provider.name().to_string(), is_healthy, elapsed)
+                    ProviderOutcome {
+                        provider: provider.name().to_string(),
+                        count: 0,
+                        elapsed_ms: start.elapsed().as_millis() as u64,
+                        error,
+                    }
                 }
             })
             .collect();
@@ -241,3 +410,44 @@ impl Default for ProviderRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scraped-site stand-in: the listing is a slice, search reaches the rest
+    struct SliceProvider;
+
+    fn font(name: &str) -> Font {
+        Font::test(name, FontProvider::Custom("Slice Fonts".to_string()))
+    }
+
+    #[async_trait]
+    impl FontProviderTrait for SliceProvider {
+        fn name(&self) -> &str { "Slice Fonts" }
+        fn base_url(&self) -> &str { "https://example.com" }
+        fn has_full_catalog(&self) -> bool { false }
+        async fn search(&self, query: &SearchQuery) -> Result<Vec<Font>> {
+            Ok(vec![font("Hidden Gem")].into_iter().filter(|f| f.name.to_lowercase().contains(&query.query)).collect())
+        }
+        async fn list_all(&self) -> Result<Vec<Font>> { Ok(vec![font("Top Pick")]) }
+        async fn get_font_family(&self, font_id: &str) -> Result<FontFamily> { Err(Error::font_not_found(self.name(), font_id)) }
+        async fn get_download_url(&self, font_id: &str) -> Result<String> { Err(Error::font_not_found(self.name(), font_id)) }
+        async fn health_check(&self) -> Result<bool> { Ok(true) }
+    }
+
+    #[tokio::test]
+    async fn test_search_all_uses_provider_search_without_full_catalog() {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Arc::new(SliceProvider));
+
+        let query = SearchQuery { query: "hidden".to_string(), ..Default::default() };
+        let results = registry.search_all(&query).await.unwrap();
+        let names: Vec<&str> = results.fonts.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Hidden Gem"]);
+
+        // Listing still returns the slice
+        let listed = registry.list_all_concurrent().await.unwrap();
+        assert_eq!(listed.fonts[0].name, "Top Pick");
+    }
+}
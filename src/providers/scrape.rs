@@ -0,0 +1,319 @@
+//! Helpers for providers that scrape HTML catalogs
+//!
+//! DaFont, FontSpace, 1001 Fonts and Font Squirrel have no API. Their
+//! providers parse listing pages with `scraper`; the parsers are pure
+//! functions over the page text, tested against saved pages in `fixtures/`.
+
+use futures::future::join_all;
+use scraper::{ElementRef, Selector};
+use std::collections::HashSet;
+
+use crate::error::Result;
+use crate::http::HttpClient;
+use crate::models::{Font, FontCategory, FontFamily, FontLicense, FontProvider, LicenseConfidence, LicenseSource};
+
+/// A font as listed on a catalog page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedFont {
+    /// The site's id for the font (`bebas-neue`)
+    pub slug: String,
+    pub name: String,
+    pub designer: Option<String>,
+    /// The site's license label (`Free for commercial use`, `OFL`, ...)
+    pub license_note: Option<String>,
+    /// Whether the site says commercial use is allowed
+    pub commercial_use: Option<bool>,
+    /// Number of styles in the family
+    pub style_count: usize,
+    pub category: Option<FontCategory>,
+    pub preview_url: Option<String>,
+    pub download_url: Option<String>,
+}
+
+impl ListedFont {
+    /// The font, with ids of the form `{prefix}-{slug}`
+    pub fn into_font(self, provider: FontProvider, prefix: &str) -> Font {
+        Font {
+            id: format!("{}-{}", prefix, self.slug),
+            name: self.name,
+            provider,
+            category: self.category,
+            variant_count: self.style_count.max(1),
+            designer: self.designer,
+            popularity: None,
+            score: None,
+            license: license(self.license_note.as_deref(), self.commercial_use),
+            // Without a label the commercial-use flag is the site's policy for every font
+            license_source: if self.license_note.is_some() { LicenseSource::ScrapedPage } else { LicenseSource::Catalog },
+            license_confidence: if self.license_note.is_some() || self.commercial_use.is_some() {
+                LicenseConfidence::Reported
+            } else {
+                LicenseConfidence::Unverified
+            },
+            preview_url: self.preview_url,
+            download_url: self.download_url,
+        }
+    }
+}
+
+/// Family details for a listed font; the sites list no per-style files
+pub fn family(font: Font) -> FontFamily {
+    FontFamily {
+        id: font.id,
+        name: font.name,
+        provider: font.provider,
+        category: font.category,
+        variants: Vec::new(),
+        subsets: Vec::new(),
+        license: font.license,
+        license_source: font.license_source,
+        license_confidence: font.license_confidence,
+        designer: font.designer,
+        description: None,
+        preview_url: font.preview_url,
+        download_url: font.download_url,
+        languages: Vec::new(),
+        last_modified: None,
+        date_added: None,
+        popularity: None,
+    }
+}
+
+/// The license a label names, else what the commercial-use flag implies
+fn license(note: Option<&str>, commercial_use: Option<bool>) -> Option<FontLicense> {
+    let lower = note.unwrap_or_default().to_lowercase();
+    if lower.contains("open font license") || lower.split_whitespace().any(|w| w == "ofl") {
+        Some(FontLicense::OFL)
+    } else if lower.contains("public domain") {
+        Some(FontLicense::PublicDomain)
+    } else {
+        match commercial_use? {
+            true => Some(FontLicense::FreeCommercial),
+            false => Some(FontLicense::Custom(note.unwrap_or("Free for personal use").to_string())),
+        }
+    }
+}
+
+/// Read a license label as a commercial-use flag; `None` when it says neither
+pub fn commercial_use(note: &str) -> Option<bool> {
+    let lower = note.to_lowercase();
+    if lower.contains("personal") || lower.contains("non-commercial") || lower.contains("demo") {
+        Some(false)
+    } else if lower.contains("commercial") || lower.contains("100% free") || lower.contains("public domain")
+        || lower.contains("open font license") || lower.contains("ofl")
+    {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// GET `url` as text
+pub async fn fetch(client: &HttpClient, url: &str) -> Result<String> {
+    client.get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+}
+
+/// Most pages of a catalog listing; at the scraped hosts' 2 requests per
+/// second this stays inside `LIST_TIMEOUT`
+pub const CATALOG_PAGES: usize = 30;
+
+/// How far to follow a listing
+#[derive(Debug, Clone, Copy)]
+pub enum Pages {
+    /// The first pages of a search, category or theme; pages after the
+    /// first that fail are skipped with a warning
+    Search,
+    /// Up to this many pages of the listing behind `list_all`, which is
+    /// cached as the provider's catalog; any page that fails fails the listing
+    Catalog(usize),
+}
+
+impl Pages {
+    fn max(self) -> usize {
+        match self {
+            Pages::Search => 5,
+            Pages::Catalog(max) => max,
+        }
+    }
+}
+
+/// Fetch page 1 of a listing, then the rest of the pages `pages` allows
+///
+/// `parse` returns a page's items and the highest page number it links to.
+/// Items are deduplicated by `key`, since listings shift while they are read.
+/// Page 1 must load; how later failures are handled depends on `pages`.
+pub async fn fetch_pages<T, U, P, K>(
+    client: &HttpClient,
+    url: U,
+    pages: Pages,
+    parse: P,
+    key: K,
+) -> Result<Vec<T>>
+where
+    U: Fn(usize) -> String,
+    P: Fn(&str) -> (Vec<T>, usize),
+    K: Fn(&T) -> String,
+{
+    let (mut items, last_page) = parse(&fetch(client, &url(1)).await?);
+    let rest = (2..=last_page.min(pages.max())).map(|page| {
+        let url = url(page);
+        async move {
+            let html = fetch(client, &url).await;
+            (url, html)
+        }
+    });
+    for (url, html) in join_all(rest).await {
+        match html {
+            Ok(html) => items.extend(parse(&html).0),
+            Err(e) if matches!(pages, Pages::Catalog(_)) => return Err(e),
+            Err(e) => tracing::warn!("Skipping listing page {}: {}", url, e),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(key(item)));
+    Ok(items)
+}
+
+/// `url` with `pairs` added as an encoded query string
+pub fn with_query(url: &str, pairs: &[(&str, &str)]) -> String {
+    match url::Url::parse_with_params(url, pairs) {
+        Ok(url) => url.to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Highest `param=N` among the links matched by `links`
+pub fn last_page(document: &scraper::Html, links: &str, param: &str) -> usize {
+    let selector = Selector::parse(links).expect("valid selector");
+    document.select(&selector)
+        .filter_map(|a| a.value().attr("href").and_then(|href| page_number(href, param)))
+        .max()
+        .unwrap_or(1)
+}
+
+/// `3` from `search.php?q=bebas&page=3` with `param` `page`
+pub fn page_number(href: &str, param: &str) -> Option<usize> {
+    href.split(['?', '&'])
+        .find_map(|pair| pair.strip_prefix(param)?.strip_prefix('='))
+        .and_then(|n| n.parse().ok())
+}
+
+/// Text content with whitespace collapsed
+pub fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text of the first element under `element` matching `selector`
+pub fn select_text(element: ElementRef, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).expect("valid selector");
+    element.select(&selector).next().map(text).filter(|t| !t.is_empty())
+}
+
+/// `href` of the first element under `element` matching `selector`
+pub fn select_href<'a>(element: ElementRef<'a>, selector: &str) -> Option<&'a str> {
+    let selector = Selector::parse(selector).expect("valid selector");
+    element.select(&selector).find_map(|a| a.value().attr("href"))
+}
+
+/// `12` from `12 Styles` or `1 Font Style`
+pub fn leading_number(text: &str) -> Option<usize> {
+    text.split_whitespace().next()?.replace(',', "").parse().ok()
+}
+
+pub fn absolute_url(href: &str, base_url: &str) -> String {
+    if href.starts_with("//") {
+        format!("https:{}", href)
+    } else if href.starts_with("http") {
+        href.to_string()
+    } else {
+        format!("{}/{}", base_url, href.trim_start_matches('/'))
+    }
+}
+
+/// `Bebas Neue` from the slug `bebas-neue`, for searching a site by id
+pub fn slug_query(slug: &str) -> String {
+    slug.split(['-', '_']).filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// Serve canned pages over HTTP on a local port, for exercising scrapers end to end
+///
+/// Each route is a path prefix (`/search.php`) with a status and body; the
+/// first matching route answers. Returns the server's base URL.
+#[cfg(test)]
+pub(crate) async fn serve(routes: Vec<(&'static str, u16, String)>) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind test server");
+    let base_url = format!("http://{}", listener.local_addr().expect("local address"));
+    let routes = std::sync::Arc::new(routes);
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let routes = std::sync::Arc::clone(&routes);
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = routes.iter()
+                    .find(|(prefix, _, _)| path.starts_with(prefix))
+                    .map(|(_, status, body)| (*status, body.as_str()))
+                    .unwrap_or((404, "not found"));
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    base_url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_helpers() {
+        assert_eq!(page_number("search.php?q=a&page=3", "page"), Some(3));
+        assert_eq!(page_number("/search?q=a&p=2", "p"), Some(2));
+        assert_eq!(page_number("/search?q=a&page=2", "p"), None);
+        assert_eq!(absolute_url("//dl.dafont.com/dl/?f=a", "https://www.dafont.com"), "https://dl.dafont.com/dl/?f=a");
+        assert_eq!(absolute_url("/fonts/a", "https://www.fontsquirrel.com"), "https://www.fontsquirrel.com/fonts/a");
+        assert_eq!(leading_number("1,204 Styles"), Some(1204));
+        assert_eq!(slug_query("bebas-neue"), "bebas neue");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages_skips_failed_search_pages_only() {
+        let base_url = serve(vec![
+            ("/list?page=1", 200, "one".to_string()),
+            ("/list?page=2", 404, "gone".to_string()),
+            ("/list?page=3", 200, "three".to_string()),
+        ]).await;
+        let client = crate::providers::create_http_client().unwrap();
+        let fetch = |pages| fetch_pages(
+            &client,
+            |page| format!("{}/list?page={}", base_url, page),
+            pages,
+            |html| (vec![html.to_string()], 3),
+            |page| page.clone(),
+        );
+        assert_eq!(fetch(Pages::Search).await.unwrap(), ["one", "three"]);
+        assert!(fetch(Pages::Catalog(CATALOG_PAGES)).await.is_err());
+    }
+}
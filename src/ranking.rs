@@ -0,0 +1,213 @@
+//! Relevance ranking for merged search results
+//!
+//! Providers hand back their catalogs unordered. This module scores every font
+//! against the query on its name, id and designer, rewarding exact, prefix,
+//! token and typo-tolerant matches, and nudges popular families upwards.
+
+use std::cmp::Ordering;
+use crate::models::Font;
+
+/// Score for a query equal to the field
+const EXACT: f32 = 100.0;
+/// Score for a field starting with the query
+const PREFIX: f32 = 80.0;
+/// Score for a field containing the query anywhere
+const SUBSTRING: f32 = 60.0;
+/// Score for every query token starting a word of the field
+const TOKENS: f32 = 50.0;
+/// Score for a match within the allowed edit distance
+const TYPO: f32 = 40.0;
+/// Penalty per edit in a typo-tolerant match
+const TYPO_PENALTY: f32 = 5.0;
+/// Largest bonus given for popularity
+const POPULARITY_BONUS: f32 = 10.0;
+/// Popularity rank at which the bonus drops to zero
+const POPULARITY_FLOOR: f32 = 2000.0;
+
+/// Relative weight of each searchable field
+const NAME_WEIGHT: f32 = 1.0;
+const ID_WEIGHT: f32 = 0.9;
+const DESIGNER_WEIGHT: f32 = 0.6;
+
+/// Scores fonts against a single query
+#[derive(Debug, Clone)]
+pub struct Ranker {
+    query: String,
+    compact: String,
+    tokens: Vec<String>,
+}
+
+impl Ranker {
+    pub fn new(query: &str) -> Self {
+        let query = normalize(query);
+        Self {
+            compact: query.replace(' ', ""),
+            tokens: query.split(' ').filter(|t| !t.is_empty()).map(str::to_string).collect(),
+            query,
+        }
+    }
+
+    /// Relevance of `font`, or `None` when it does not match at all
+    ///
+    /// An empty query matches everything and ranks by popularity alone.
+    pub fn score(&self, font: &Font) -> Option<f32> {
+        let relevance = if self.query.is_empty() {
+            0.0
+        } else {
+            let fields = [
+                (Some(font.name.as_str()), NAME_WEIGHT),
+                (Some(font.id.as_str()), ID_WEIGHT),
+                (font.designer.as_deref(), DESIGNER_WEIGHT),
+            ];
+            fields.iter()
+                .filter_map(|(text, weight)| text.and_then(|t| self.score_field(t)).map(|s| s * weight))
+                .fold(None, |best: Option<f32>, s| Some(best.map_or(s, |b| b.max(s))))?
+        };
+
+        Some(relevance + popularity_bonus(font.popularity))
+    }
+
+    /// Score every font, drop the non-matches and sort best first
+    pub fn rank(&self, fonts: Vec<Font>) -> Vec<Font> {
+        let mut ranked: Vec<Font> = fonts.into_iter()
+            .filter_map(|mut font| {
+                font.score = Some(self.score(&font)?);
+                Some(font)
+            })
+            .collect();
+        ranked.sort_by(by_score);
+        ranked
+    }
+
+    fn score_field(&self, text: &str) -> Option<f32> {
+        let text = normalize(text);
+        let compact = text.replace(' ', "");
+
+        if text == self.query || compact == self.compact {
+            return Some(EXACT);
+        }
+        if text.starts_with(&self.query) || compact.starts_with(&self.compact) {
+            // Prefer fields the query covers more of ("inter" ranks Inter Tight above Interstate)
+            return Some(PREFIX + 10.0 * self.coverage(&compact));
+        }
+        if text.contains(&self.query) || compact.contains(&self.compact) {
+            return Some(SUBSTRING);
+        }
+
+        let words: Vec<&str> = text.split(' ').filter(|w| !w.is_empty()).collect();
+        if self.tokens.iter().all(|t| words.iter().any(|w| w.starts_with(t.as_str()))) {
+            return Some(TOKENS);
+        }
+
+        self.typo_distance(&compact, &words)
+            .map(|edits| TYPO - TYPO_PENALTY * edits as f32 + 5.0 * self.coverage(&compact))
+    }
+
+    /// Fraction of the field the query spans, capped at 1
+    fn coverage(&self, compact: &str) -> f32 {
+        (self.compact.len() as f32 / compact.len().max(1) as f32).min(1.0)
+    }
+
+    /// Smallest number of edits that makes the query match, if within tolerance
+    fn typo_distance(&self, compact: &str, words: &[&str]) -> Option<usize> {
+        let whole = strsim::levenshtein(&self.compact, compact);
+        let whole = (whole <= max_edits(&self.compact)).then_some(whole);
+
+        let per_token = self.tokens.iter()
+            .map(|t| {
+                words.iter()
+                    .map(|w| strsim::levenshtein(t, w))
+                    .min()
+                    .filter(|d| *d <= max_edits(t))
+            })
+            .sum::<Option<usize>>();
+
+        match (whole, per_token) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Rank `fonts` for `query` (convenience for a one-off [`Ranker`])
+pub fn rank(fonts: Vec<Font>, query: &str) -> Vec<Font> {
+    Ranker::new(query).rank(fonts)
+}
+
+/// Best score first, then [`by_name`]
+pub fn by_score(a: &Font, b: &Font) -> Ordering {
+    b.score.unwrap_or(0.0)
+        .partial_cmp(&a.score.unwrap_or(0.0))
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| by_name(a, b))
+}
+
+/// Total, deterministic order by name, then provider, then id
+pub fn by_name(a: &Font, b: &Font) -> Ordering {
+    a.name.to_lowercase().cmp(&b.name.to_lowercase())
+        .then_with(|| a.provider.name().cmp(b.provider.name()))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Edits tolerated for a term of this length
+fn max_edits(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+fn popularity_bonus(rank: Option<u32>) -> f32 {
+    match rank {
+        Some(rank) if rank > 0 => {
+            let decay = (rank as f32).ln() / POPULARITY_FLOOR.ln();
+            POPULARITY_BONUS * (1.0 - decay).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Lowercase and turn separators into single spaces
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FontProvider;
+
+    fn font(name: &str, popularity: Option<u32>) -> Font {
+        Font { popularity, ..Font::test(name, FontProvider::GoogleFonts) }
+    }
+
+    #[test]
+    fn test_match_kinds_order() {
+        let fonts = vec![
+            font("Roboto Slab", None),
+            font("Noto Sans", None),
+            font("Roboto", None),
+            font("Open Sans", None),
+        ];
+        let ranked = rank(fonts, "roboto");
+        let names: Vec<&str> = ranked.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Roboto", "Roboto Slab"]);
+
+        let ranked = rank(vec![font("Montserrat", None), font("Lato", None)], "montserat");
+        assert_eq!(ranked.len(), 1);
+        assert!(ranked[0].score.unwrap() < SUBSTRING);
+    }
+
+    #[test]
+    fn test_popularity_breaks_ties() {
+        let fonts = vec![font("Open Sans Arabic", Some(900)), font("Open Sans Hebrew", Some(3))];
+        let ranked = rank(fonts, "open sans");
+        assert_eq!(ranked[0].name, "Open Sans Hebrew");
+        assert_eq!(rank(vec![font("Lato", None)], "").len(), 1);
+    }
+}
@@ -3,12 +3,17 @@
 //! Provides unified search across all font providers with optimized
 //! concurrent fetching for maximum performance.
 
-use anyhow::Result;
 use rayon::prelude::*;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::models::{FontFamily, FontProvider, SearchQuery, SearchResults, FontCategory};
+use crate::cache::{CachePolicy, CatalogCache};
+use crate::config::Config;
+use crate::dedup;
+use crate::error::{Error, Result};
+use crate::models::{Font, FontFamily, FontProvider, ProviderOutcome, SearchQuery, SearchResults, FontCategory};
+use crate::stream::{self, ProviderBatch};
+use futures::stream::BoxStream;
 use crate::providers::ProviderRegistry;
 use crate::cdn::{CdnUrlGenerator, FontCdnUrls};
 
@@ -19,13 +24,48 @@ pub struct FontSearch {
 
 impl FontSearch {
     /// Create a new font search engine with default providers
+    ///
+    /// Provider catalogs are cached on disk according to `Config::default()`.
     pub fn new() -> Result<Self> {
-        let registry = ProviderRegistry::with_defaults()?;
+        Self::with_cache_policy(CachePolicy::Normal)
+    }
+    
+    /// Create a search engine with default providers and the given cache policy
+    pub fn with_cache_policy(policy: CachePolicy) -> Result<Self> {
+        Self::with_config(&Config::default(), policy)
+    }
+    
+    /// Create a search engine whose HTTP client and cache follow `config`
+    ///
+    /// `Config::offline` forces [`CachePolicy::Offline`].
+    pub fn with_config(config: &Config, policy: CachePolicy) -> Result<Self> {
+        let policy = if config.offline { CachePolicy::Offline } else { policy };
+        let cache = CatalogCache::from_config(config).with_policy(policy);
+        let registry = ProviderRegistry::with_config(config)?.with_cache(cache);
         Ok(Self {
             registry: Arc::new(registry),
         })
     }
     
+    /// Create a search engine that runs only from the local catalog snapshot
+    ///
+    /// No network requests are made; missing snapshot data is an error.
+    pub fn offline() -> Result<Self> {
+        Self::with_cache_policy(CachePolicy::Offline)
+    }
+    
+    /// Let background catalog refreshes finish, waiting at most `limit`
+    ///
+    /// Call before exiting when using [`CachePolicy::StaleWhileRevalidate`].
+    pub async fn wait_for_refreshes(&self, limit: std::time::Duration) {
+        self.registry.wait_for_refreshes(limit).await;
+    }
+    
+    /// Whether this search engine only serves the offline snapshot
+    pub fn is_offline(&self) -> bool {
+        self.registry.is_offline()
+    }
+    
     /// Create with a custom registry
     pub fn with_registry(registry: ProviderRegistry) -> Self {
         Self {
@@ -56,24 +96,27 @@ impl FontSearch {
         self.registry.search_all(&query).await
     }
     
+    /// Stream per-provider result batches as each provider finishes
+    pub fn search_stream(&self, query: &SearchQuery) -> BoxStream<'static, ProviderBatch> {
+        self.registry.search_stream(query)
+    }
+    
+    /// Up to `n` of the best matches that arrive within `deadline`
+    ///
+    /// Meant for interactive pickers: slow providers are simply left out.
+    pub async fn search_first(&self, query: &SearchQuery, n: usize, deadline: Duration) -> Vec<Font> {
+        stream::first_n(self.search_stream(query), n, deadline).await
+    }
+    
     /// Search for fonts by category
     pub async fn search_by_category(&self, category: FontCategory) -> Result<SearchResults> {
         let query = SearchQuery {
             query: String::new(),
-            category: Some(category.clone()),
+            category: Some(category),
             ..Default::default()
         };
         
-        let mut results = self.registry.search_all(&query).await?;
-        
-        // Filter by category
-        results.fonts = results.fonts
-            .into_iter()
-            .filter(|f| f.category.as_ref() == Some(&category))
-            .collect();
-        results.total = results.fonts.len();
-        
-        Ok(results)
+        self.registry.search_all(&query).await
     }
     
     /// List all available fonts from all providers (concurrent)
@@ -81,6 +124,14 @@ impl FontSearch {
         self.registry.list_all_concurrent().await
     }
     
+    /// List one page of the catalog in a stable name order
+    ///
+    /// Page through large catalogs with `limit` plus `offset`, or with the
+    /// `next_cursor` of the previous page; rows never repeat across pages.
+    pub async fn list_page(&self, query: SearchQuery) -> Result<SearchResults> {
+        self.registry.list_page(&query).await
+    }
+    
     /// List all with timing information
     pub async fn list_all_timed(&self) -> Result<(SearchResults, Duration)> {
         let start = Instant::now();
@@ -91,13 +142,10 @@ impl FontSearch {
     
     /// Get detailed information about a specific font
     pub async fn get_font_details(&self, provider: &FontProvider, font_id: &str) -> Result<FontFamily> {
-        for p in self.registry.providers() {
-            if p.name() == provider.name() {
-                return p.get_font_family(font_id).await;
-            }
+        match self.registry.get(provider) {
+            Some(p) => p.get_font_family(font_id).await.map_err(|e| e.with_provider(p.name())),
+            None => Err(Error::ProviderNotFound { provider: provider.name().to_string() }),
         }
-        
-        Err(anyhow::anyhow!("Provider not found: {:?}", provider))
     }
     
     /// Get CDN URLs for a font for preview/usage
@@ -114,12 +162,20 @@ impl FontSearch {
     pub async fn health_check(&self) -> Vec<(String, bool)> {
         self.registry.health_check_all().await
             .into_iter()
-            .map(|(name, healthy, _)| (name, healthy))
+            .map(|o| (o.provider.clone(), o.is_ok()))
             .collect()
     }
     
     /// Check health with timing information
     pub async fn health_check_timed(&self) -> Vec<(String, bool, Duration)> {
+        self.registry.health_check_all().await
+            .into_iter()
+            .map(|o| (o.provider.clone(), o.is_ok(), Duration::from_millis(o.elapsed_ms)))
+            .collect()
+    }
+    
+    /// Check health of all providers, with the reason each failing one failed
+    pub async fn health_report(&self) -> Vec<ProviderOutcome> {
         self.registry.health_check_all().await
     }
     
@@ -127,24 +183,30 @@ impl FontSearch {
     pub async fn get_stats(&self) -> Result<FontStats> {
         let (results, elapsed) = self.list_all_timed().await?;
         
-        let mut stats = FontStats::default();
-        stats.total_fonts = results.total;
-        stats.providers_count = results.providers_searched.len();
-        stats.providers = results.providers_searched;
-        stats.fetch_time_ms = elapsed.as_millis() as u64;
+        let families = dedup::merge(&results.fonts, self.registry.priority());
+        
+        let mut stats = FontStats {
+            total_fonts: results.total,
+            unique_families: families.len(),
+            providers_count: results.providers_searched.len(),
+            providers: results.providers_searched,
+            fetch_time_ms: elapsed.as_millis() as u64,
+            outcomes: results.outcomes,
+            ..Default::default()
+        };
         
-        // Count by category using parallel processing (Rayon)
-        let category_counts: Vec<(Option<FontCategory>, usize)> = results.fonts
+        // Count unique families by category using parallel processing (Rayon)
+        let category_counts: Vec<(Option<FontCategory>, usize)> = families
             .par_iter()
             .fold(
-                || std::collections::HashMap::new(),
-                |mut acc, font| {
-                    *acc.entry(font.category.clone()).or_insert(0) += 1;
+                std::collections::HashMap::new,
+                |mut acc, family| {
+                    *acc.entry(family.category.clone()).or_insert(0) += 1;
                     acc
                 }
             )
             .reduce(
-                || std::collections::HashMap::new(),
+                std::collections::HashMap::new,
                 |mut a, b| {
                     for (k, v) in b {
                         *a.entry(k).or_insert(0) += v;
@@ -173,7 +235,10 @@ impl FontSearch {
 /// Font statistics with performance metrics
 #[derive(Debug, Default, serde::Serialize)]
 pub struct FontStats {
+    /// Rows across all providers, counting each provider's copy of a family
     pub total_fonts: usize,
+    /// Distinct families once duplicates across providers are merged
+    pub unique_families: usize,
     pub providers_count: usize,
     pub providers: Vec<String>,
     pub serif_count: usize,
@@ -183,4 +248,6 @@ pub struct FontStats {
     pub monospace_count: usize,
     pub uncategorized_count: usize,
     pub fetch_time_ms: u64,
+    /// How each provider fared while gathering the statistics
+    pub outcomes: Vec<ProviderOutcome>,
 }
@@ -0,0 +1,167 @@
+//! Streaming search results
+//!
+//! Instead of waiting for the slowest provider, a streaming search yields one
+//! [`ProviderBatch`] per provider as soon as that provider answers, fails or
+//! times out. [`first_n`] builds on it for interactive pickers that want a
+//! handful of good matches quickly rather than every match eventually.
+
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::time::{timeout, Duration, Instant};
+
+use crate::error::ProviderError;
+use crate::models::{Font, ProviderOutcome, SearchQuery};
+use crate::providers::FontProviderTrait;
+use crate::ranking;
+
+/// How a provider's part of a search ended
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    Ok,
+    Error(ProviderError),
+    Timeout,
+}
+
+/// The results of one provider, delivered as soon as it finishes
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderBatch {
+    pub provider: String,
+    pub status: BatchStatus,
+    /// Time from the start of the search until this provider finished
+    pub elapsed: Duration,
+    /// Matching fonts, best first (empty unless the status is `Ok`)
+    pub fonts: Vec<Font>,
+}
+
+impl ProviderBatch {
+    pub fn is_ok(&self) -> bool {
+        self.status == BatchStatus::Ok
+    }
+
+    /// The failure, if any, as a typed error
+    pub fn error(&self) -> Option<ProviderError> {
+        match &self.status {
+            BatchStatus::Ok => None,
+            BatchStatus::Error(e) => Some(e.clone()),
+            BatchStatus::Timeout => Some(ProviderError::Timeout {
+                message: format!("no answer within {}s", self.elapsed.as_secs()),
+            }),
+        }
+    }
+    
+    /// Summary of this batch for a results report
+    pub fn outcome(&self) -> ProviderOutcome {
+        ProviderOutcome {
+            provider: self.provider.clone(),
+            count: self.fonts.len(),
+            elapsed_ms: self.elapsed.as_millis() as u64,
+            error: self.error(),
+        }
+    }
+}
+
+/// Fetch a provider's fonts, never waiting longer than `limit`
+///
+/// Providers with a full catalog return all of it. The others return their
+/// own search results for `query`, since their listing is only a slice;
+/// without a query they return that listing.
+pub(crate) async fn fetch_batch(
+    provider: Arc<dyn FontProviderTrait>,
+    query: Option<SearchQuery>,
+    limit: Duration,
+) -> ProviderBatch {
+    let start = Instant::now();
+    let name = provider.name().to_string();
+
+    let fetch = async {
+        match &query {
+            Some(query) if !provider.has_full_catalog() => provider.search(query).await,
+            _ => provider.list_all().await,
+        }
+    };
+    let (status, fonts) = match timeout(limit, fetch).await {
+        Ok(Ok(fonts)) => {
+            tracing::debug!("Provider {} returned {} fonts", name, fonts.len());
+            (BatchStatus::Ok, fonts)
+        }
+        Ok(Err(e)) => {
+            tracing::debug!("Error fetching from {}: {:#}", name, e);
+            match ProviderError::from(&e) {
+                ProviderError::Timeout { .. } => (BatchStatus::Timeout, Vec::new()),
+                error => (BatchStatus::Error(error), Vec::new()),
+            }
+        }
+        Err(_) => {
+            tracing::debug!("Timeout fetching from {}", name);
+            (BatchStatus::Timeout, Vec::new())
+        }
+    };
+
+    ProviderBatch {
+        provider: name,
+        status,
+        elapsed: start.elapsed(),
+        fonts,
+    }
+}
+
+/// Collect up to `n` of the best fonts that arrive before `deadline`
+///
+/// Stops early once `n` fonts are in; providers still running at that point
+/// are dropped. The fonts gathered so far are merged and re-ranked by score.
+pub async fn first_n<S>(mut stream: S, n: usize, deadline: Duration) -> Vec<Font>
+where
+    S: Stream<Item = ProviderBatch> + Unpin,
+{
+    let mut fonts = Vec::new();
+    let collect = async {
+        while let Some(batch) = stream.next().await {
+            fonts.extend(batch.fonts);
+            if fonts.len() >= n {
+                break;
+            }
+        }
+    };
+    // Hitting the deadline just means we return what we have
+    let _ = timeout(deadline, collect).await;
+
+    fonts.sort_by(ranking::by_score);
+    fonts.truncate(n);
+    fonts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FontProvider;
+
+    fn batch(provider: &str, scores: &[f32]) -> ProviderBatch {
+        ProviderBatch {
+            provider: provider.to_string(),
+            status: BatchStatus::Ok,
+            elapsed: Duration::ZERO,
+            fonts: scores.iter()
+                .enumerate()
+                .map(|(i, score)| Font {
+                    id: format!("{}-{}", provider, i),
+                    score: Some(*score),
+                    ..Font::test(&format!("{} {}", provider, i), FontProvider::Custom(provider.to_string()))
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_n_stops_early_and_reranks() {
+        let batches = futures::stream::iter(vec![
+            batch("a", &[10.0, 5.0]),
+            batch("b", &[50.0]),
+            batch("c", &[99.0]),
+        ]);
+        let fonts = first_n(batches, 3, Duration::from_secs(1)).await;
+        let ids: Vec<&str> = fonts.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, ["b-0", "a-0", "a-1"]);
+    }
+}